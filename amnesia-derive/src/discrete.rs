@@ -0,0 +1,470 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Expr, Fields, Lit, RangeLimits, Type, UnOp};
+
+/// Describes the traits of a discrete space of `amnesia`.
+pub struct DiscreteSpace {
+    module: &'static str,
+    marker_trait: &'static str,
+    discrete_trait: &'static str,
+    values: &'static str,
+}
+
+impl DiscreteSpace {
+    pub const ACTION: Self = Self {
+        module: "action",
+        marker_trait: "Action",
+        discrete_trait: "DiscreteAction",
+        values: "ACTIONS",
+    };
+
+    pub const OBSERVATION: Self = Self {
+        module: "observation",
+        marker_trait: "Observation",
+        discrete_trait: "DiscreteObservation",
+        values: "OBSERVATIONS",
+    };
+
+    pub fn derive(&self, input: &DeriveInput) -> syn::Result<TokenStream> {
+        if !input.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &input.generics,
+                format!(
+                    "{} can't be derived for generic types.",
+                    self.discrete_trait
+                ),
+            ));
+        }
+
+        let variants = match &input.data {
+            Data::Struct(data) => vec![Variant::new(quote!(Self), &data.fields)?],
+            Data::Enum(data) => data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    Variant::new(quote!(Self::#ident), &variant.fields)
+                })
+                .collect::<syn::Result<Vec<_>>>()?,
+            Data::Union(_) => {
+                return Err(syn::Error::new_spanned(
+                    input,
+                    format!("{} can't be derived for unions.", self.discrete_trait),
+                ))
+            }
+        };
+        if variants.is_empty() {
+            return Err(syn::Error::new_spanned(
+                input,
+                format!(
+                    "{} can't be derived for enums without variants.",
+                    self.discrete_trait
+                ),
+            ));
+        }
+
+        let module = format_ident!("{}", self.module);
+        let marker_trait = format_ident!("{}", self.marker_trait);
+        let discrete_trait = format_ident!("{}", self.discrete_trait);
+        let discrete_trait = quote!(::amnesia::#module::#discrete_trait);
+        let values = format_ident!("{}", self.values);
+
+        let name = &input.ident;
+        let count = self.sum_count(&variants, &discrete_trait);
+        let element = self.sum_element(&variants, &discrete_trait);
+        let arms = self.index_arms(&variants, &discrete_trait);
+
+        Ok(quote! {
+            impl ::amnesia::#module::#marker_trait for #name {}
+
+            impl #discrete_trait for #name {
+                const #values: &'static [Self] = &{
+                    const COUNT: usize = #count;
+                    let mut values = [{
+                        let index = 0usize;
+                        #element
+                    }; COUNT];
+                    let mut index = 0usize;
+                    while index < COUNT {
+                        values[index] = #element;
+                        index += 1;
+                    }
+                    values
+                };
+
+                fn index(&self) -> usize {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        })
+    }
+
+    /// Number of values of all variants.
+    fn sum_count(&self, variants: &[Variant], discrete_trait: &TokenStream) -> TokenStream {
+        let counts = variants
+            .iter()
+            .map(|variant| variant.count(self, discrete_trait));
+        quote!(0usize #(+ #counts)*)
+    }
+
+    /// Expression that builds the value at position `index`.
+    fn sum_element(&self, variants: &[Variant], discrete_trait: &TokenStream) -> TokenStream {
+        let offsets = self.offsets(variants, discrete_trait);
+        let (last, init) = variants
+            .split_last()
+            .expect("There must be at least one variant.");
+        let last_offset = &offsets[init.len()];
+        let mut element = last.element(self, discrete_trait, &quote!((index - (#last_offset))));
+
+        for (variant, (offset, next_offset)) in init
+            .iter()
+            .zip(offsets.iter().zip(offsets.iter().skip(1)))
+            .rev()
+        {
+            let variant_element =
+                variant.element(self, discrete_trait, &quote!((index - (#offset))));
+            element = quote! {
+                if index < (#next_offset) {
+                    #variant_element
+                } else {
+                    #element
+                }
+            };
+        }
+        element
+    }
+
+    /// Match arms that compute the index of each variant.
+    fn index_arms(&self, variants: &[Variant], discrete_trait: &TokenStream) -> Vec<TokenStream> {
+        self.offsets(variants, discrete_trait)
+            .into_iter()
+            .zip(variants)
+            .map(|(offset, variant)| {
+                let pattern = variant.pattern();
+                let index = variant.index(self, discrete_trait);
+                quote!(#pattern => (#offset) + #index,)
+            })
+            .collect()
+    }
+
+    /// Position of the first value of each variant.
+    fn offsets(&self, variants: &[Variant], discrete_trait: &TokenStream) -> Vec<TokenStream> {
+        variants
+            .iter()
+            .scan(quote!(0usize), |offset, variant| {
+                let current = offset.clone();
+                let count = variant.count(self, discrete_trait);
+                *offset = quote!(#current + #count);
+                Some(current)
+            })
+            .collect()
+    }
+}
+
+/// A constructor of the type, either the struct or one of the variants of the enum.
+struct Variant {
+    path: TokenStream,
+    fields: Vec<Field>,
+    named: bool,
+}
+
+impl Variant {
+    fn new(path: TokenStream, fields: &Fields) -> syn::Result<Self> {
+        Ok(Self {
+            path,
+            fields: fields
+                .iter()
+                .enumerate()
+                .map(|(position, field)| Field::new(position, field))
+                .collect::<syn::Result<_>>()?,
+            named: matches!(fields, Fields::Named(_)),
+        })
+    }
+
+    /// Number of values of the variant, the product of the number of values of the fields.
+    fn count(&self, space: &DiscreteSpace, discrete_trait: &TokenStream) -> TokenStream {
+        let counts = self
+            .fields
+            .iter()
+            .map(|field| field.kind.count(space, discrete_trait));
+        quote!((1usize #(* #counts)*))
+    }
+
+    /// Expression that builds the value at position `index` of the variant.
+    ///
+    /// The first field is the most significant.
+    fn element(
+        &self,
+        space: &DiscreteSpace,
+        discrete_trait: &TokenStream,
+        index: &TokenStream,
+    ) -> TokenStream {
+        let elements = self.fields.iter().enumerate().map(|(position, field)| {
+            let stride = self.stride(position, space, discrete_trait);
+            let count = field.kind.count(space, discrete_trait);
+            field.kind.element(
+                space,
+                discrete_trait,
+                &quote!(((#index / #stride) % #count)),
+            )
+        });
+        self.construct(elements)
+    }
+
+    /// Expression that computes the position of the value bound by [`Variant::pattern`].
+    fn index(&self, space: &DiscreteSpace, discrete_trait: &TokenStream) -> TokenStream {
+        let indices = self.fields.iter().enumerate().map(|(position, field)| {
+            let stride = self.stride(position, space, discrete_trait);
+            let index = field.kind.index(discrete_trait, field);
+            quote!(#index * #stride)
+        });
+        quote!((0usize #(+ #indices)*))
+    }
+
+    /// Product of the number of values of the fields after `position`.
+    fn stride(
+        &self,
+        position: usize,
+        space: &DiscreteSpace,
+        discrete_trait: &TokenStream,
+    ) -> TokenStream {
+        let counts = self.fields[position + 1..]
+            .iter()
+            .map(|field| field.kind.count(space, discrete_trait));
+        quote!((1usize #(* #counts)*))
+    }
+
+    fn pattern(&self) -> TokenStream {
+        self.construct(self.fields.iter().map(|field| {
+            let binding = &field.binding;
+            quote!(#binding)
+        }))
+    }
+
+    fn construct(&self, values: impl Iterator<Item = TokenStream>) -> TokenStream {
+        let path = &self.path;
+        if self.fields.is_empty() {
+            path.clone()
+        } else if self.named {
+            let members = self.fields.iter().map(|field| &field.member);
+            quote!(#path { #(#members: #values),* })
+        } else {
+            quote!(#path(#(#values),*))
+        }
+    }
+}
+
+struct Field {
+    member: syn::Member,
+    binding: syn::Ident,
+    kind: FieldKind,
+}
+
+impl Field {
+    fn new(position: usize, field: &syn::Field) -> syn::Result<Self> {
+        Ok(Self {
+            member: field
+                .ident
+                .clone()
+                .map_or_else(|| syn::Member::from(position), syn::Member::from),
+            binding: format_ident!("__field_{}", position),
+            kind: FieldKind::new(field)?,
+        })
+    }
+}
+
+enum FieldKind {
+    /// A type that implements the same discrete trait being derived.
+    Discrete(Type),
+    Bool,
+    /// An integer bounded by `#[amnesia(range = ...)]`.
+    Range {
+        ty: Type,
+        start: Box<Expr>,
+        end: Box<Expr>,
+        inclusive: bool,
+    },
+}
+
+impl FieldKind {
+    fn new(field: &syn::Field) -> syn::Result<Self> {
+        let mut range = None;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("amnesia"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("range") {
+                    match meta.value()?.parse()? {
+                        Expr::Range(expr) => match (expr.start, expr.end) {
+                            (Some(start), Some(end)) => {
+                                let inclusive = matches!(expr.limits, RangeLimits::Closed(_));
+                                // Only literal bounds can be checked before the expansion
+                                if let (Some(first), Some(last)) =
+                                    (literal_value(&start), literal_value(&end))
+                                {
+                                    if first > last || (first == last && !inclusive) {
+                                        return Err(meta.error("The range must not be empty."));
+                                    }
+                                }
+                                range = Some(Self::Range {
+                                    ty: field.ty.clone(),
+                                    start,
+                                    end,
+                                    inclusive,
+                                });
+                                Ok(())
+                            }
+                            _ => Err(meta.error("The range must have a start and an end.")),
+                        },
+                        _ => Err(meta.error("Expected a range like `0..10`.")),
+                    }
+                } else {
+                    Err(meta.error("Unsupported amnesia attribute."))
+                }
+            })?;
+        }
+
+        Ok(match (range, &field.ty) {
+            (Some(range), _) => range,
+            (None, Type::Path(path)) if path.path.is_ident("bool") => Self::Bool,
+            (None, ty) => Self::Discrete(ty.clone()),
+        })
+    }
+
+    fn count(&self, space: &DiscreteSpace, discrete_trait: &TokenStream) -> TokenStream {
+        match self {
+            Self::Discrete(ty) => {
+                let values = format_ident!("{}", space.values);
+                quote!(<#ty as #discrete_trait>::#values.len())
+            }
+            Self::Bool => quote!(2usize),
+            Self::Range {
+                ty: _,
+                start,
+                end,
+                inclusive,
+            } => {
+                let inclusive = usize::from(*inclusive);
+                quote!((((#end) as i128 - (#start) as i128) as usize + #inclusive))
+            }
+        }
+    }
+
+    fn element(
+        &self,
+        space: &DiscreteSpace,
+        discrete_trait: &TokenStream,
+        index: &TokenStream,
+    ) -> TokenStream {
+        match self {
+            Self::Discrete(ty) => {
+                let values = format_ident!("{}", space.values);
+                quote!(<#ty as #discrete_trait>::#values[#index])
+            }
+            Self::Bool => quote!((#index == 1)),
+            Self::Range {
+                ty,
+                start,
+                end: _,
+                inclusive: _,
+            } => quote!((((#start) as i128 + #index as i128) as #ty)),
+        }
+    }
+
+    /// Expression that computes the position of the value of `field`, whose value is
+    /// bound by [`Variant::pattern`].
+    fn index(&self, discrete_trait: &TokenStream, field: &Field) -> TokenStream {
+        let binding = &field.binding;
+        match self {
+            Self::Discrete(_) => quote!(#discrete_trait::index(#binding)),
+            Self::Bool => quote!((*#binding as usize)),
+            Self::Range {
+                ty: _,
+                start,
+                end,
+                inclusive,
+            } => {
+                let member = &field.member;
+                let (comparison, limits) = if *inclusive {
+                    (quote!(<=), "..=")
+                } else {
+                    (quote!(<), "..")
+                };
+                // Tokens are printed spaced, like `- 2`
+                let unspaced = |tokens: TokenStream| tokens.to_string().replace(' ', "");
+                let message = format!(
+                    "Field `{}` must be in the range `{}{limits}{}`, but is {{}}.",
+                    quote!(#member),
+                    unspaced(quote!(#start)),
+                    unspaced(quote!(#end)),
+                );
+                quote!({
+                    let value = *#binding as i128;
+                    assert!(
+                        (#start) as i128 <= value && value #comparison (#end) as i128,
+                        #message,
+                        value
+                    );
+                    (value - (#start) as i128) as usize
+                })
+            }
+        }
+    }
+}
+
+/// Value of an integer literal, which may be negated.
+fn literal_value(expr: &Expr) -> Option<i128> {
+    match expr {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Int(int) => int.base10_parse().ok(),
+            _ => None,
+        },
+        Expr::Unary(expr) if matches!(expr.op, UnOp::Neg(_)) => {
+            literal_value(&expr.expr).map(|value: i128| -value)
+        }
+        Expr::Paren(expr) => literal_value(&expr.expr),
+        Expr::Group(expr) => literal_value(&expr.expr),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::DiscreteSpace;
+
+    fn derive_error(input: syn::DeriveInput) -> String {
+        DiscreteSpace::OBSERVATION
+            .derive(&input)
+            .map(|_| ())
+            .expect_err("The derive must be rejected.")
+            .to_string()
+    }
+
+    #[test]
+    fn empty_ranges_are_rejected() {
+        for input in [
+            parse_quote!(
+                struct Empty(#[amnesia(range = 3..3)] i32);
+            ),
+            parse_quote!(
+                struct Reversed(#[amnesia(range = 2..=-1)] i32);
+            ),
+        ] {
+            assert_eq!(derive_error(input), "The range must not be empty.");
+        }
+    }
+
+    #[test]
+    fn single_value_ranges_are_accepted() {
+        let input = parse_quote!(
+            struct Single(#[amnesia(range = -1..=-1)] i32, #[amnesia(range = 0..1)] u8);
+        );
+        assert!(DiscreteSpace::OBSERVATION.derive(&input).is_ok());
+    }
+}
@@ -0,0 +1,42 @@
+//! Derive macros for the discrete spaces of `amnesia`.
+
+mod discrete;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+use crate::discrete::DiscreteSpace;
+
+/// Derives `amnesia::action::Action` and `amnesia::action::DiscreteAction`.
+///
+/// Supports fieldless enums, enums whose variants hold other `DiscreteAction`s,
+/// and structs, where the actions are the Cartesian product of the values of
+/// the fields. Fields can be
+/// * types that implement `DiscreteAction`
+/// * `bool`
+/// * integers annotated with `#[amnesia(range = START..END)]` or `#[amnesia(range = START..=END)]`
+#[proc_macro_derive(DiscreteAction, attributes(amnesia))]
+pub fn derive_discrete_action(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    DiscreteSpace::ACTION
+        .derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `amnesia::observation::Observation` and `amnesia::observation::DiscreteObservation`.
+///
+/// Supports fieldless enums, enums whose variants hold other `DiscreteObservation`s,
+/// and structs, where the observations are the Cartesian product of the values of
+/// the fields. Fields can be
+/// * types that implement `DiscreteObservation`
+/// * `bool`
+/// * integers annotated with `#[amnesia(range = START..END)]` or `#[amnesia(range = START..=END)]`
+#[proc_macro_derive(DiscreteObservation, attributes(amnesia))]
+pub fn derive_discrete_observation(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    DiscreteSpace::OBSERVATION
+        .derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
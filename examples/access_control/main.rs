@@ -0,0 +1,143 @@
+use amnesia::{
+    action::DiscreteAction,
+    agent::{Agent, PolicyAgent},
+    environment::{DiscreteEnvironment, Environment},
+    observation::DiscreteObservation,
+    policy::{epsilon_greedy::EpsilonGreedyPolicy, Policy},
+    random_number_generator::{RandomNumberGeneratorFacade, Xoshiro256PlusPlus},
+    reinforcement_learning::{
+        average_reward::{DifferentialSARSA, RLearning},
+        PolicyEstimator,
+    },
+    space::DiscreteSpace,
+};
+
+const SERVERS: usize = 10;
+const FREE_PROBABILITY: f64 = 0.06;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteObservation)]
+enum Priority {
+    Low,
+    Medium,
+    High,
+    Highest,
+}
+
+impl Priority {
+    fn reward(self) -> f64 {
+        match self {
+            Priority::Low => 1.,
+            Priority::Medium => 2.,
+            Priority::High => 4.,
+            Priority::Highest => 8.,
+        }
+    }
+}
+
+/// Number of free servers and priority of the customer at the head of the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteObservation)]
+struct Queue(#[amnesia(range = 0..=SERVERS)] usize, Priority);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteAction)]
+enum Decision {
+    Accept,
+    Reject,
+}
+
+struct Gatekeeper {
+    policy: EpsilonGreedyPolicy<Decision, Queue, Xoshiro256PlusPlus>,
+}
+
+impl Agent for Gatekeeper {
+    type Action = Decision;
+    type Observation = Queue;
+
+    fn act(&self, observation: &Self::Observation) -> Self::Action {
+        self.policy.act(observation)
+    }
+
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.policy.policy_improvemnt(observation, action_values);
+    }
+}
+
+impl PolicyAgent for Gatekeeper {
+    type Policy = EpsilonGreedyPolicy<Decision, Queue, Xoshiro256PlusPlus>;
+
+    fn policy(&self) -> &Self::Policy {
+        &self.policy
+    }
+}
+
+/// Customers of random priority arrive at a queue forever. Accepting a customer
+/// takes a free server and is rewarded by its priority, while busy servers become
+/// free with a fixed probability on every step.
+struct AccessControl {
+    queue: Queue,
+    rng: Xoshiro256PlusPlus,
+}
+
+impl Environment for AccessControl {
+    type Agent = Gatekeeper;
+
+    fn get_observation(&mut self, _agent: &Self::Agent) -> Option<Queue> {
+        Some(self.queue)
+    }
+
+    fn receive_action(&mut self, _agent: &Self::Agent, action: &Decision) -> f64 {
+        let Queue(free_servers, priority) = self.queue;
+        let (free_servers, reward) = match action {
+            Decision::Accept if free_servers > 0 => (free_servers - 1, priority.reward()),
+            _ => (free_servers, 0.),
+        };
+        let freed = (0..SERVERS - free_servers)
+            .filter(|_| self.rng.random() < FREE_PROBABILITY)
+            .count();
+        self.queue = Queue(
+            free_servers + freed,
+            Priority::from_index(self.rng.random_range(0..Priority::COUNT)),
+        );
+        reward
+    }
+}
+
+impl DiscreteEnvironment for AccessControl {
+    fn observation_space(&self) -> DiscreteSpace<Queue> {
+        DiscreteSpace::observations()
+    }
+
+    fn action_space(&self) -> DiscreteSpace<Decision> {
+        DiscreteSpace::actions()
+    }
+}
+
+fn main() {
+    const STEPS: usize = 2000000;
+    const ALPHA: f64 = 0.01;
+    const BETA: f64 = 0.01;
+    const EPSILON: f64 = 0.1;
+    const SEED: u64 = 42;
+
+    let rng = Xoshiro256PlusPlus::from_seed(SEED);
+
+    let mut access_control = AccessControl {
+        queue: Queue(SERVERS, Priority::Low),
+        rng: rng.fork(),
+    };
+
+    println!("Differential SARSA");
+    let mut agent = Gatekeeper {
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
+    };
+    let report = DifferentialSARSA::<AccessControl>::new(STEPS, ALPHA, BETA)
+        .policy_search(&mut access_control, &mut agent);
+    print!("{report}");
+
+    println!("R-Learning");
+    let mut agent = Gatekeeper {
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
+    };
+    let report = RLearning::<AccessControl>::new(STEPS, ALPHA, BETA)
+        .policy_search(&mut access_control, &mut agent);
+    print!("{report}");
+}
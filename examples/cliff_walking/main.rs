@@ -1,47 +1,35 @@
 use amnesia::{
-    action::{Action, DiscreteAction},
-    agent::{Agent, DiscreteAgent},
-    environment::{Environment, EpisodicEnvironment},
-    observation::{DiscreteObservation, Observation},
+    action::DiscreteAction,
+    agent::{Agent, PolicyAgent},
+    environment::{DiscreteEnvironment, Environment, EpisodeEnd, EpisodicEnvironment},
+    observation::DiscreteObservation,
     policy::{epsilon_greedy::EpsilonGreedyPolicy, Policy},
-    random_number_generator::RandomNumberGeneratorFacade,
+    random_number_generator::Xoshiro256PlusPlus,
     reinforcement_learning::{
         monte_carlo::{
             ConstantAlphaMonteCarlo, EveryVisitMonteCarlo, FirstVisitMonteCarlo,
             IncrementalMonteCarlo,
         },
-        temporal_difference::{ExpectedSARSA, QLearning, SARSA},
+        temporal_difference::{
+            ExpectedSARSA, NStepSARSA, QLearning, SARSALambda, TraceType, TrueOnlineSARSALambda,
+            WatkinsQLambda, SARSA,
+        },
         PolicyEstimator,
     },
+    schedule::{Schedule, ScheduleTiming},
+    space::DiscreteSpace,
+    value_table::Sparse,
 };
 
 const LEN: usize = 12;
 
-const fn build_cliff_path() -> [CliffPath; LEN * 4] {
-    let mut cliff = [CliffPath(0, 0); LEN * 4];
-    let mut x = 0;
-    let mut y = 0;
-    while y < 4 {
-        while x < LEN {
-            cliff[y * LEN + x] = CliffPath(x, y);
-            x += 1;
-        }
-        x = 0;
-        y += 1;
-    }
-    cliff
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct CliffPath(usize, usize);
-
-impl Observation for CliffPath {}
-
-impl DiscreteObservation for CliffPath {
-    const OBSERVATIONS: &'static [Self] = &build_cliff_path();
-}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteObservation)]
+struct CliffPath(
+    #[amnesia(range = 0..LEN)] usize,
+    #[amnesia(range = 0..4)] usize,
+);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteAction)]
 enum Walk {
     Up,
     Down,
@@ -49,14 +37,8 @@ enum Walk {
     Right,
 }
 
-impl Action for Walk {}
-
-impl DiscreteAction for Walk {
-    const ACTIONS: &'static [Self] = &[Self::Up, Self::Down, Self::Left, Self::Right];
-}
-
 struct CliffWalker {
-    policy: EpsilonGreedyPolicy<Walk, CliffPath, RandFacade>,
+    policy: EpsilonGreedyPolicy<Walk, CliffPath, Xoshiro256PlusPlus>,
 }
 
 impl Agent for CliffWalker {
@@ -67,31 +49,40 @@ impl Agent for CliffWalker {
         self.policy.act(observation)
     }
 
-    fn policy_improvemnt(
-        &mut self,
-        action: &Self::Action,
-        observation: &Self::Observation,
-        value: f64,
-    ) {
-        self.policy.policy_improvemnt(action, observation, value);
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.policy.policy_improvemnt(observation, action_values);
+    }
+
+    fn on_episode_end(&mut self) {
+        self.policy.on_episode_end();
+    }
+
+    fn on_step_end(&mut self) {
+        self.policy.on_step_end();
     }
 }
 
-impl DiscreteAgent<Walk, CliffPath> for CliffWalker {
-    fn action_probability(&self, action: &Walk, observation: &CliffPath) -> f64 {
-        if self.act(observation).eq(action) {
-            1.
-        } else {
-            0.
-        }
+impl PolicyAgent for CliffWalker {
+    type Policy = EpsilonGreedyPolicy<Walk, CliffPath, Xoshiro256PlusPlus>;
+
+    fn policy(&self) -> &Self::Policy {
+        &self.policy
     }
 }
 
+const MAX_STEPS: usize = 500;
+
 struct Cliff {
     walker_position: CliffPath,
     steps_taken: usize,
 }
 
+impl Cliff {
+    fn is_terminal(&self) -> bool {
+        matches!(self.walker_position, CliffPath(x, 0) if x != 0)
+    }
+}
+
 impl Environment for Cliff {
     type Agent = CliffWalker;
 
@@ -99,10 +90,7 @@ impl Environment for Cliff {
         &mut self,
         _agent: &Self::Agent,
     ) -> Option<<Self::Agent as amnesia::agent::Agent>::Observation> {
-        match self.walker_position {
-            CliffPath(x, 0) if x != 0 => None,
-            _ => Some(self.walker_position).filter(|_| self.steps_taken < 500),
-        }
+        Some(self.walker_position).filter(|_| !self.is_terminal() && self.steps_taken < MAX_STEPS)
     }
 
     fn receive_action(
@@ -112,28 +100,24 @@ impl Environment for Cliff {
     ) -> f64 {
         self.steps_taken += 1;
 
-        if self.steps_taken < 500 {
-            let (delta_x, delta_y) = match action {
-                Walk::Up => (0isize, 1isize),
-                Walk::Down => (0, -1),
-                Walk::Left => (-1, 0),
-                Walk::Right => (1, 0),
-            };
-
-            self.walker_position = {
-                let CliffPath(x, y) = self.walker_position;
-                CliffPath(
-                    x.saturating_add_signed(delta_x).min(LEN - 1),
-                    y.saturating_add_signed(delta_y).min(4 - 1),
-                )
-            };
-            match self.walker_position {
-                CliffPath(x, 0) if x == LEN - 1 => 10.,
-                CliffPath(x, 0) if x != 0 => -100.,
-                _ => -1.,
-            }
-        } else {
-            -100.
+        let (delta_x, delta_y) = match action {
+            Walk::Up => (0isize, 1isize),
+            Walk::Down => (0, -1),
+            Walk::Left => (-1, 0),
+            Walk::Right => (1, 0),
+        };
+
+        self.walker_position = {
+            let CliffPath(x, y) = self.walker_position;
+            CliffPath(
+                x.saturating_add_signed(delta_x).min(LEN - 1),
+                y.saturating_add_signed(delta_y).min(4 - 1),
+            )
+        };
+        match self.walker_position {
+            CliffPath(x, 0) if x == LEN - 1 => 10.,
+            CliffPath(x, 0) if x != 0 => -100.,
+            _ => -1.,
         }
     }
 }
@@ -147,13 +131,24 @@ impl EpisodicEnvironment for Cliff {
     fn final_observation(&self, _agent: &Self::Agent) -> <Self::Agent as Agent>::Observation {
         self.walker_position
     }
+
+    /// Episodes that run out of steps are cut short, not terminated.
+    fn episode_end(&self) -> EpisodeEnd {
+        if self.is_terminal() {
+            EpisodeEnd::Terminated
+        } else {
+            EpisodeEnd::Truncated
+        }
+    }
 }
 
-struct RandFacade;
+impl DiscreteEnvironment for Cliff {
+    fn observation_space(&self) -> DiscreteSpace<CliffPath> {
+        DiscreteSpace::observations()
+    }
 
-impl RandomNumberGeneratorFacade for RandFacade {
-    fn random(&self) -> f64 {
-        rand::random()
+    fn action_space(&self) -> DiscreteSpace<Walk> {
+        DiscreteSpace::actions()
     }
 }
 
@@ -162,6 +157,10 @@ fn main() {
     const RETURN_DISCOUNT: f64 = 1. - (1. / 16.);
     const ALPHA: f64 = 1. / 64.;
     const EPSILON: f64 = 0.1;
+    const LAMBDA: f64 = 0.9;
+    const SEED: u64 = 42;
+
+    let rng = Xoshiro256PlusPlus::from_seed(SEED);
 
     let mut cliff = Cliff {
         walker_position: CliffPath(0, 0),
@@ -170,48 +169,127 @@ fn main() {
 
     println!("First Visit Monte Carlo");
     let mut agent = CliffWalker {
-        policy: EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap(),
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
     };
-    FirstVisitMonteCarlo::<Cliff>::new(RETURN_DISCOUNT, EPISODES)
+    let report = FirstVisitMonteCarlo::<Cliff>::new(RETURN_DISCOUNT, EPISODES)
         .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
 
     println!("Every Visit Monte Carlo");
     let mut agent = CliffWalker {
-        policy: EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap(),
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
     };
-    EveryVisitMonteCarlo::<Cliff>::new(RETURN_DISCOUNT, EPISODES)
+    let report = EveryVisitMonteCarlo::<Cliff>::new(RETURN_DISCOUNT, EPISODES)
         .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
 
     println!("Incremental Monte Carlo");
     let mut agent = CliffWalker {
-        policy: EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap(),
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
     };
-    IncrementalMonteCarlo::<Cliff>::new(RETURN_DISCOUNT, EPISODES)
+    let report = IncrementalMonteCarlo::<Cliff>::new(RETURN_DISCOUNT, EPISODES)
         .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
 
     println!("Constant Alpha Monte Carlo");
     let mut agent = CliffWalker {
-        policy: EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap(),
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
     };
-    ConstantAlphaMonteCarlo::<Cliff>::new(ALPHA, RETURN_DISCOUNT, EPISODES)
+    let report = ConstantAlphaMonteCarlo::<Cliff>::new(ALPHA, RETURN_DISCOUNT, EPISODES)
         .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
 
     println!("Q-Learning");
     let mut agent = CliffWalker {
-        policy: EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap(),
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
     };
-    QLearning::<Cliff>::new(EPISODES, ALPHA, RETURN_DISCOUNT).policy_search(&mut cliff, &mut agent);
+    let report = QLearning::<Cliff>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
+
+    println!("Q-Learning with sparse storage");
+    let mut agent = CliffWalker {
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
+    };
+    let report = QLearning::<Cliff, Sparse>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
 
     println!("SARSA");
     let mut agent = CliffWalker {
-        policy: EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap(),
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
     };
-    SARSA::<Cliff>::new(EPISODES, ALPHA, RETURN_DISCOUNT).policy_search(&mut cliff, &mut agent);
+    let report =
+        SARSA::<Cliff>::new(EPISODES, ALPHA, RETURN_DISCOUNT).policy_search(&mut cliff, &mut agent);
+    print!("{report}");
 
     println!("ExpectedSARSA");
     let mut agent = CliffWalker {
-        policy: EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap(),
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
+    };
+    let report = ExpectedSARSA::<Cliff>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
+
+    println!("4-step SARSA");
+    let mut agent = CliffWalker {
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
     };
-    ExpectedSARSA::<Cliff>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+    let report = NStepSARSA::<Cliff>::new(EPISODES, 4, ALPHA, RETURN_DISCOUNT)
         .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
+
+    println!("SARSA(λ)");
+    let mut agent = CliffWalker {
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
+    };
+    let report = SARSALambda::<Cliff>::new(
+        EPISODES,
+        LAMBDA,
+        ALPHA,
+        RETURN_DISCOUNT,
+        TraceType::Replacing,
+    )
+    .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
+
+    println!("Watkins Q(λ)");
+    let mut agent = CliffWalker {
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
+    };
+    let report = WatkinsQLambda::<Cliff>::new(
+        EPISODES,
+        LAMBDA,
+        ALPHA,
+        RETURN_DISCOUNT,
+        TraceType::Accumulating,
+    )
+    .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
+
+    println!("True Online SARSA(λ)");
+    let mut agent = CliffWalker {
+        policy: EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap(),
+    };
+    let report = TrueOnlineSARSALambda::<Cliff>::new(EPISODES, LAMBDA, ALPHA, RETURN_DISCOUNT)
+        .policy_search(&mut cliff, &mut agent);
+    print!("{report}");
+
+    println!("SARSA with linearly decaying ε");
+    let mut agent = CliffWalker {
+        policy: EpsilonGreedyPolicy::with_schedule(
+            Schedule::Linear {
+                start: 0.5,
+                end: 0.01,
+                duration: 1000,
+            },
+            ScheduleTiming::PerEpisode,
+            rng.fork(),
+        )
+        .unwrap(),
+    };
+    let report =
+        SARSA::<Cliff>::new(EPISODES, ALPHA, RETURN_DISCOUNT).policy_search(&mut cliff, &mut agent);
+    print!("{report}");
+    println!("Final ε of {}", agent.policy.epsilon());
 }
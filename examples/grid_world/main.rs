@@ -0,0 +1,265 @@
+use std::{collections::HashSet, env, fs};
+
+use amnesia::{
+    action::DiscreteAction,
+    agent::{Agent, PolicyAgent},
+    environment::{DiscreteEnvironment, Environment, EpisodeEnd, EpisodicEnvironment},
+    observation::Observation,
+    policy::{epsilon_greedy::EpsilonGreedyPolicy, Policy},
+    random_number_generator::Xoshiro256PlusPlus,
+    reinforcement_learning::{
+        monte_carlo::FirstVisitMonteCarlo,
+        temporal_difference::{ExpectedSARSA, QLearning},
+        PolicyEstimator,
+    },
+    schedule::{Schedule, ScheduleTiming},
+    space::{ActionMask, DiscreteSpace},
+    value_table::QTable,
+};
+
+/// Map used when no map file is given. `#` are walls, `S` is the start and `G` the goal.
+const DEFAULT_MAP: &str = "\
+##########
+#S...#...#
+#.##.#.#.#
+#.#..#.#.#
+#.#.##.#.#
+#......#G#
+##########";
+
+/// Position on the map, only known once the map is loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Cell(usize, usize);
+
+impl Observation for Cell {}
+
+impl Cell {
+    /// The cell reached by `action`, which might be a wall.
+    fn moved(self, action: Move) -> Cell {
+        let Cell(x, y) = self;
+        match action {
+            Move::Up => Cell(x, y.saturating_sub(1)),
+            Move::Down => Cell(x, y + 1),
+            Move::Left => Cell(x.saturating_sub(1), y),
+            Move::Right => Cell(x + 1, y),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteAction)]
+enum Move {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+struct Explorer {
+    policy: EpsilonGreedyPolicy<Move, Cell, Xoshiro256PlusPlus>,
+}
+
+impl Agent for Explorer {
+    type Action = Move;
+    type Observation = Cell;
+
+    fn act(&self, observation: &Self::Observation) -> Self::Action {
+        self.policy.act(observation)
+    }
+
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.policy.policy_improvemnt(observation, action_values);
+    }
+
+    fn on_episode_end(&mut self) {
+        self.policy.on_episode_end();
+    }
+}
+
+impl PolicyAgent for Explorer {
+    type Policy = EpsilonGreedyPolicy<Move, Cell, Xoshiro256PlusPlus>;
+
+    fn policy(&self) -> &Self::Policy {
+        &self.policy
+    }
+}
+
+const MAX_STEPS: usize = 200;
+
+struct GridWorld {
+    open_cells: DiscreteSpace<Cell>,
+    floor: HashSet<Cell>,
+    start: Cell,
+    goal: Cell,
+    position: Cell,
+    steps_taken: usize,
+}
+
+impl GridWorld {
+    /// # Panics
+    /// Panics if the map doesn't have exactly one start and one goal.
+    fn parse(map: &str) -> Self {
+        let mut open_cells = vec![];
+        let mut start = vec![];
+        let mut goal = vec![];
+        for (y, line) in map.lines().enumerate() {
+            for (x, tile) in line.chars().enumerate() {
+                let cell = Cell(x, y);
+                match tile {
+                    '#' => continue,
+                    'S' => start.push(cell),
+                    'G' => goal.push(cell),
+                    _ => {}
+                }
+                open_cells.push(cell);
+            }
+        }
+        let (&[start], &[goal]) = (start.as_slice(), goal.as_slice()) else {
+            panic!("The map must have exactly one start and one goal.");
+        };
+        Self {
+            floor: open_cells.iter().copied().collect(),
+            open_cells: DiscreteSpace::from_elements(open_cells),
+            start,
+            goal,
+            position: start,
+            steps_taken: 0,
+        }
+    }
+}
+
+impl Environment for GridWorld {
+    type Agent = Explorer;
+
+    fn get_observation(
+        &mut self,
+        _agent: &Self::Agent,
+    ) -> Option<<Self::Agent as Agent>::Observation> {
+        Some(self.position).filter(|_| self.position != self.goal && self.steps_taken < MAX_STEPS)
+    }
+
+    fn receive_action(
+        &mut self,
+        _agent: &Self::Agent,
+        action: &<Self::Agent as Agent>::Action,
+    ) -> f64 {
+        self.steps_taken += 1;
+
+        let next = self.position.moved(*action);
+        // Bumping into a wall, or out of the map, keeps the explorer in place
+        if self.floor.contains(&next) {
+            self.position = next;
+        }
+        -1.
+    }
+}
+
+impl EpisodicEnvironment for GridWorld {
+    fn reset_environment(&mut self) {
+        self.position = self.start;
+        self.steps_taken = 0;
+    }
+
+    fn final_observation(&self, _agent: &Self::Agent) -> <Self::Agent as Agent>::Observation {
+        self.position
+    }
+
+    fn episode_end(&self) -> EpisodeEnd {
+        if self.position == self.goal {
+            EpisodeEnd::Terminated
+        } else {
+            EpisodeEnd::Truncated
+        }
+    }
+}
+
+impl DiscreteEnvironment for GridWorld {
+    fn observation_space(&self) -> DiscreteSpace<Cell> {
+        self.open_cells.clone()
+    }
+
+    fn action_space(&self) -> DiscreteSpace<Move> {
+        DiscreteSpace::actions()
+    }
+
+    /// Moves into walls are illegal.
+    fn action_mask(&self) -> ActionMask<Cell, Move> {
+        let floor = self.floor.clone();
+        ActionMask::new(self.action_space(), move |cell: &Cell, action: &Move| {
+            floor.contains(&cell.moved(*action))
+        })
+    }
+}
+
+/// Follows the legal greedy actions of `action_value` from the start, up to [`MAX_STEPS`].
+fn greedy_path(
+    grid_world: &mut GridWorld,
+    agent: &Explorer,
+    action_value: &QTable<Cell, Move>,
+) -> Vec<Cell> {
+    let action_mask = grid_world.action_mask();
+    grid_world.reset_environment();
+    let mut path = vec![grid_world.position];
+    while let Some(observation) = grid_world.get_observation(agent) {
+        let action = action_value.legal_greedy_action(&observation, &action_mask);
+        grid_world.receive_action(agent, &action);
+        path.push(grid_world.position);
+    }
+    path
+}
+
+fn main() {
+    const EPISODES: usize = 5000;
+    const RETURN_DISCOUNT: f64 = 0.99;
+    const ALPHA: f64 = 0.1;
+    const SEED: u64 = 42;
+
+    let map = env::args()
+        .nth(1)
+        .map(|path| fs::read_to_string(path).expect("The map file must be readable."))
+        .unwrap_or_else(|| DEFAULT_MAP.to_string());
+    let mut grid_world = GridWorld::parse(&map);
+    println!("{} open cells", grid_world.open_cells.len());
+
+    let rng = Xoshiro256PlusPlus::from_seed(SEED);
+    let explorer = |grid_world: &GridWorld, rng: Xoshiro256PlusPlus| Explorer {
+        policy: EpsilonGreedyPolicy::with_spaces(
+            grid_world.observation_space(),
+            grid_world.action_space(),
+            Schedule::Constant(0.1),
+            ScheduleTiming::PerEpisode,
+            rng,
+        )
+        .unwrap()
+        .with_action_mask(grid_world.action_mask()),
+    };
+
+    println!("First Visit Monte Carlo");
+    let mut agent = explorer(&grid_world, rng.fork());
+    let report = FirstVisitMonteCarlo::<GridWorld>::new(RETURN_DISCOUNT, EPISODES)
+        .policy_search(&mut grid_world, &mut agent);
+    print!("{report}");
+    println!(
+        "Greedy path {:?}",
+        greedy_path(&mut grid_world, &agent, &report.action_values)
+    );
+
+    println!("Q-Learning");
+    let mut agent = explorer(&grid_world, rng.fork());
+    let report = QLearning::<GridWorld>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .policy_search(&mut grid_world, &mut agent);
+    print!("{report}");
+    println!(
+        "Greedy path {:?}",
+        greedy_path(&mut grid_world, &agent, &report.action_values)
+    );
+
+    println!("ExpectedSARSA");
+    let mut agent = explorer(&grid_world, rng.fork());
+    let report = ExpectedSARSA::<GridWorld>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .policy_search(&mut grid_world, &mut agent);
+    print!("{report}");
+    println!(
+        "Greedy path {:?}",
+        greedy_path(&mut grid_world, &agent, &report.action_values)
+    );
+}
@@ -1,33 +1,30 @@
+use std::time::Duration;
+
 use amnesia::{
-    action::{Action, DiscreteAction},
-    agent::{Agent, DiscreteAgent},
-    environment::{Environment, EpisodicEnvironment},
-    observation::{DiscreteObservation, Observation},
-    policy::{epsilon_greedy::EpsilonGreedyPolicy, Policy},
-    random_number_generator::RandomNumberGeneratorFacade,
+    action::DiscreteAction,
+    agent::{Agent, DiscreteAgent, PolicyAgent},
+    model::{TabularMdpEnvironment, TabularModel},
+    observation::DiscreteObservation,
+    policy::{epsilon_greedy::EpsilonGreedyPolicy, softmax::SoftmaxPolicy, Greedy, Policy},
+    random_number_generator::{RandomNumberGeneratorFacade, Xoshiro256PlusPlus},
     reinforcement_learning::{
+        dynamic_programming::{PolicyIteration, Sweep, ValueIteration},
         monte_carlo::{
-            ConstantAlphaMonteCarlo, EveryVisitMonteCarlo, FirstVisitMonteCarlo,
-            IncrementalMonteCarlo,
+            ConstantAlphaMonteCarlo, EveryVisitMonteCarlo, ExploringStartsMonteCarlo,
+            FirstVisitMonteCarlo, ImportanceSampling, IncrementalMonteCarlo, OffPolicyMonteCarlo,
         },
         temporal_difference::{ExpectedSARSA, QLearning, SARSA},
-        PolicyEstimator,
+        PolicyEstimator, StoppingCriterion,
     },
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteAction)]
 pub enum RoverAction {
     MoveLeft,
     MoveRight,
 }
 
-impl Action for RoverAction {}
-
-impl DiscreteAction for RoverAction {
-    const ACTIONS: &'static [Self] = &[Self::MoveLeft, Self::MoveRight];
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteObservation)]
 pub enum MarsSpace {
     S1,
     S2,
@@ -38,23 +35,33 @@ pub enum MarsSpace {
     S7,
 }
 
-impl Observation for MarsSpace {}
-
-impl DiscreteObservation for MarsSpace {
-    const OBSERVATIONS: &'static [Self] = &[
-        Self::S1,
-        Self::S2,
-        Self::S3,
-        Self::S4,
-        Self::S5,
-        Self::S6,
-        Self::S7,
-    ];
+struct Rover(EpsilonGreedyPolicy<RoverAction, MarsSpace, Xoshiro256PlusPlus>);
+
+impl Agent for Rover {
+    type Action = RoverAction;
+    type Observation = MarsSpace;
+
+    fn act(&self, observation: &Self::Observation) -> Self::Action {
+        self.0.act(observation)
+    }
+
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.0.policy_improvemnt(observation, action_values);
+    }
 }
 
-struct Rover(EpsilonGreedyPolicy<RoverAction, MarsSpace, RandFacade>);
+impl PolicyAgent for Rover {
+    type Policy = EpsilonGreedyPolicy<RoverAction, MarsSpace, Xoshiro256PlusPlus>;
 
-impl Agent for Rover {
+    fn policy(&self) -> &Self::Policy {
+        &self.0
+    }
+}
+
+/// Explores with Boltzmann exploration, cooling down as the episodes go by.
+struct SoftmaxRover(SoftmaxPolicy<RoverAction, MarsSpace, Xoshiro256PlusPlus>);
+
+impl Agent for SoftmaxRover {
     type Action = RoverAction;
     type Observation = MarsSpace;
 
@@ -62,51 +69,72 @@ impl Agent for Rover {
         self.0.act(observation)
     }
 
-    fn policy_improvemnt(
-        &mut self,
-        action: &Self::Action,
-        observation: &Self::Observation,
-        value: f64,
-    ) {
-        self.0.policy_improvemnt(action, observation, value);
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.0.policy_improvemnt(observation, action_values);
+    }
+
+    fn on_episode_end(&mut self) {
+        self.0.on_episode_end();
     }
 }
 
-impl DiscreteAgent<RoverAction, MarsSpace> for Rover {
-    fn action_probability(&self, action: &RoverAction, observation: &MarsSpace) -> f64 {
-        if self.act(observation).eq(action) {
-            1.
-        } else {
-            0.
-        }
+impl PolicyAgent for SoftmaxRover {
+    type Policy = SoftmaxPolicy<RoverAction, MarsSpace, Xoshiro256PlusPlus>;
+
+    fn policy(&self) -> &Self::Policy {
+        &self.0
+    }
+}
+
+/// Explores uniformly, to generate the episodes of off-policy searches.
+struct RandomRover(Xoshiro256PlusPlus);
+
+impl Agent for RandomRover {
+    type Action = RoverAction;
+    type Observation = MarsSpace;
+
+    fn act(&self, _observation: &Self::Observation) -> Self::Action {
+        RoverAction::from_index(self.0.random_range(0..RoverAction::COUNT))
     }
+
+    fn policy_improvemnt(&mut self, _observation: &Self::Observation, _action_values: &[f64]) {}
 }
 
-struct Mars {
-    rover_position: MarsSpace,
+impl DiscreteAgent<RoverAction, MarsSpace> for RandomRover {
+    fn action_probability(&self, _action: &RoverAction, _observation: &MarsSpace) -> f64 {
+        1. / RoverAction::COUNT as f64
+    }
 }
 
-impl Environment for Mars {
-    type Agent = Rover;
-
-    fn get_observation(
-        &mut self,
-        _agent: &Self::Agent,
-    ) -> Option<<Self::Agent as Agent>::Observation> {
-        match self.rover_position {
-            MarsSpace::S1 => None,
-            MarsSpace::S7 => None,
-            _ => Some(self.rover_position),
-        }
+struct GreedyRover(Greedy<RoverAction, MarsSpace, Xoshiro256PlusPlus>);
+
+impl Agent for GreedyRover {
+    type Action = RoverAction;
+    type Observation = MarsSpace;
+
+    fn act(&self, observation: &Self::Observation) -> Self::Action {
+        self.0.act(observation)
+    }
+
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.0.policy_improvemnt(observation, action_values);
     }
+}
 
-    fn receive_action(
-        &mut self,
-        _agent: &Self::Agent,
-        action: &<Self::Agent as Agent>::Action,
-    ) -> f64 {
-        self.rover_position = match action {
-            RoverAction::MoveLeft => match self.rover_position {
+impl PolicyAgent for GreedyRover {
+    type Policy = Greedy<RoverAction, MarsSpace, Xoshiro256PlusPlus>;
+
+    fn policy(&self) -> &Self::Policy {
+        &self.0
+    }
+}
+
+struct Mars;
+
+impl Mars {
+    fn next_position(rover_position: MarsSpace, action: RoverAction) -> MarsSpace {
+        match action {
+            RoverAction::MoveLeft => match rover_position {
                 MarsSpace::S1 => MarsSpace::S1,
                 MarsSpace::S2 => MarsSpace::S1,
                 MarsSpace::S3 => MarsSpace::S2,
@@ -115,7 +143,7 @@ impl Environment for Mars {
                 MarsSpace::S6 => MarsSpace::S5,
                 MarsSpace::S7 => MarsSpace::S6,
             },
-            RoverAction::MoveRight => match self.rover_position {
+            RoverAction::MoveRight => match rover_position {
                 MarsSpace::S1 => MarsSpace::S2,
                 MarsSpace::S2 => MarsSpace::S3,
                 MarsSpace::S3 => MarsSpace::S4,
@@ -124,8 +152,11 @@ impl Environment for Mars {
                 MarsSpace::S6 => MarsSpace::S7,
                 MarsSpace::S7 => MarsSpace::S7,
             },
-        };
-        match self.rover_position {
+        }
+    }
+
+    fn reward(rover_position: MarsSpace) -> f64 {
+        match rover_position {
             MarsSpace::S1 => 1.,
             MarsSpace::S2 => 0.,
             MarsSpace::S3 => 0.,
@@ -135,66 +166,155 @@ impl Environment for Mars {
             MarsSpace::S7 => 10.,
         }
     }
-}
-
-impl EpisodicEnvironment for Mars {
-    fn reset_environment(&mut self) {
-        self.rover_position = MarsSpace::S4;
-    }
 
-    fn final_observation(&self, _agent: &Self::Agent) -> <Self::Agent as Agent>::Observation {
-        self.rover_position
+    fn is_terminal(rover_position: MarsSpace) -> bool {
+        matches!(rover_position, MarsSpace::S1 | MarsSpace::S7)
     }
 }
 
-struct RandFacade;
-
-impl RandomNumberGeneratorFacade for RandFacade {
-    fn random(&self) -> f64 {
-        rand::random()
+impl TabularModel<MarsSpace, RoverAction> for Mars {
+    fn transitions(
+        &self,
+        observation: &MarsSpace,
+        action: &RoverAction,
+    ) -> impl Iterator<Item = (f64, MarsSpace, f64, bool)> {
+        let next_position = Mars::next_position(*observation, *action);
+        (!Mars::is_terminal(*observation))
+            .then_some((
+                1.,
+                next_position,
+                Mars::reward(next_position),
+                Mars::is_terminal(next_position),
+            ))
+            .into_iter()
     }
 }
 
+type MarsEnvironment = TabularMdpEnvironment<Mars, Rover, Xoshiro256PlusPlus>;
+type SoftmaxMarsEnvironment = TabularMdpEnvironment<Mars, SoftmaxRover, Xoshiro256PlusPlus>;
+type RandomMarsEnvironment = TabularMdpEnvironment<Mars, RandomRover, Xoshiro256PlusPlus>;
+
 fn main() {
     const EPISODES: usize = 10000000;
     const RETURN_DISCOUNT: f64 = 1.;
     const EPSILON: f64 = 0.05;
     const ALPHA: f64 = 0.1;
+    const TEMPERATURE: f64 = 10.;
+    const SEED: u64 = 42;
+
+    let rng = Xoshiro256PlusPlus::from_seed(SEED);
+
+    let stopping_criterion = StoppingCriterion::Any(vec![
+        StoppingCriterion::MaxEpisodes(EPISODES),
+        StoppingCriterion::ValueChangeTolerance {
+            tolerance: 1e-9,
+            window: 1000,
+        },
+        StoppingCriterion::WallClock(Duration::from_secs(10)),
+    ]);
 
-    let mut mars = Mars {
-        rover_position: MarsSpace::S4,
-    };
+    let mut mars = MarsEnvironment::new(Mars, &[(1., MarsSpace::S4)], rng.fork())
+        .expect("Mars is a valid model.");
+
+    println!("Value Iteration");
+    let report = ValueIteration::new(RETURN_DISCOUNT, 1e-9, 1000)
+        .with_sweep(Sweep::InPlace)
+        .solve(mars.model());
+    print!("{report}");
+
+    println!("Policy Iteration");
+    let report = PolicyIteration::new(RETURN_DISCOUNT, 1e-9, 1000).solve(mars.model());
+    print!("{report}");
 
     println!("First Visit Monte Carlo");
-    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    FirstVisitMonteCarlo::<Mars>::new(RETURN_DISCOUNT, EPISODES)
+    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = FirstVisitMonteCarlo::<MarsEnvironment>::new(RETURN_DISCOUNT, EPISODES)
+        .with_stopping_criterion(stopping_criterion.clone())
         .policy_search(&mut mars, &mut agent);
+    print!("{report}");
 
     println!("Every Visit Monte Carlo");
-    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    EveryVisitMonteCarlo::<Mars>::new(RETURN_DISCOUNT, EPISODES)
+    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = EveryVisitMonteCarlo::<MarsEnvironment>::new(RETURN_DISCOUNT, EPISODES)
+        .with_stopping_criterion(stopping_criterion.clone())
         .policy_search(&mut mars, &mut agent);
+    print!("{report}");
+
+    println!("Exploring Starts Monte Carlo");
+    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report =
+        ExploringStartsMonteCarlo::<MarsEnvironment, _>::new(RETURN_DISCOUNT, EPISODES, rng.fork())
+            .with_stopping_criterion(stopping_criterion.clone())
+            .policy_search(&mut mars, &mut agent);
+    print!("{report}");
 
     println!("Incremental Monte Carlo");
-    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    IncrementalMonteCarlo::<Mars>::new(RETURN_DISCOUNT, EPISODES)
+    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = IncrementalMonteCarlo::<MarsEnvironment>::new(RETURN_DISCOUNT, EPISODES)
+        .with_stopping_criterion(stopping_criterion.clone())
         .policy_search(&mut mars, &mut agent);
+    print!("{report}");
 
     println!("Constant Alpha Monte Carlo");
-    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    ConstantAlphaMonteCarlo::<Mars>::new(ALPHA, RETURN_DISCOUNT, EPISODES)
+    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = ConstantAlphaMonteCarlo::<MarsEnvironment>::new(ALPHA, RETURN_DISCOUNT, EPISODES)
+        .with_stopping_criterion(stopping_criterion.clone())
         .policy_search(&mut mars, &mut agent);
+    print!("{report}");
 
     println!("Q-Learning");
-    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    QLearning::<Mars>::new(EPISODES, ALPHA, RETURN_DISCOUNT).policy_search(&mut mars, &mut agent);
+    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = QLearning::<MarsEnvironment>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .with_stopping_criterion(stopping_criterion.clone())
+        .policy_search(&mut mars, &mut agent);
+    print!("{report}");
 
     println!("SARSA");
-    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    SARSA::<Mars>::new(EPISODES, ALPHA, RETURN_DISCOUNT).policy_search(&mut mars, &mut agent);
+    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = SARSA::<MarsEnvironment>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .with_stopping_criterion(stopping_criterion.clone())
+        .policy_search(&mut mars, &mut agent);
+    print!("{report}");
 
     println!("ExpectedSARSA");
-    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    ExpectedSARSA::<Mars>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+    let mut agent = Rover(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = ExpectedSARSA::<MarsEnvironment>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .with_stopping_criterion(stopping_criterion.clone())
         .policy_search(&mut mars, &mut agent);
+    print!("{report}");
+
+    println!("ExpectedSARSA with Softmax exploration");
+    let mut softmax_mars = SoftmaxMarsEnvironment::new(Mars, &[(1., MarsSpace::S4)], rng.fork())
+        .expect("Mars is a valid model.");
+    let mut agent = SoftmaxRover(
+        SoftmaxPolicy::new(TEMPERATURE, rng.fork())
+            .unwrap()
+            .with_annealing(0.999, 0.01),
+    );
+    let report = ExpectedSARSA::<SoftmaxMarsEnvironment>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .with_stopping_criterion(stopping_criterion.clone())
+        .policy_search(&mut softmax_mars, &mut agent);
+    print!("{report}");
+    println!("Final temperature of {}", agent.0.temperature());
+
+    let mut random_mars = RandomMarsEnvironment::new(Mars, &[(1., MarsSpace::S4)], rng.fork())
+        .expect("Mars is a valid model.");
+    for importance_sampling in [
+        ImportanceSampling::Ordinary,
+        ImportanceSampling::Weighted,
+        ImportanceSampling::PerDecision,
+    ] {
+        println!("Off-Policy Monte Carlo with {importance_sampling:?} Importance Sampling");
+        let mut agent = RandomRover(rng.fork());
+        let mut target = GreedyRover(Greedy::new(rng.fork()));
+        let report = OffPolicyMonteCarlo::<RandomMarsEnvironment, _>::new(
+            &mut target,
+            RETURN_DISCOUNT,
+            EPISODES,
+            importance_sampling,
+        )
+        .with_stopping_criterion(stopping_criterion.clone())
+        .policy_search(&mut random_mars, &mut agent);
+        print!("{report}");
+    }
 }
@@ -1,47 +1,43 @@
+use std::marker::PhantomData;
+
 use amnesia::{
-    action::{Action, DiscreteAction},
-    agent::{Agent, DiscreteAgent},
-    environment::{Environment, EpisodicEnvironment},
-    observation::{DiscreteObservation, Observation},
-    policy::{epsilon_greedy::EpsilonGreedyPolicy, Policy},
-    random_number_generator::RandomNumberGeneratorFacade,
+    action::DiscreteAction,
+    agent::{Agent, PolicyAgent},
+    environment::{DiscreteEnvironment, Environment, EpisodicEnvironment},
+    observation::DiscreteObservation,
+    policy::{epsilon_greedy::EpsilonGreedyPolicy, ucb::UcbPolicy, Policy},
+    random_number_generator::{RandomNumberGeneratorFacade, Xoshiro256PlusPlus},
     reinforcement_learning::{
         monte_carlo::ConstantAlphaMonteCarlo,
         monte_carlo::FirstVisitMonteCarlo,
         monte_carlo::{EveryVisitMonteCarlo, IncrementalMonteCarlo},
-        temporal_difference::{ExpectedSARSA, QLearning, SARSA},
+        temporal_difference::{
+            DoubleExpectedSARSA, DoubleQLearning, ExpectedSARSA, QLearning, SARSA,
+        },
         PolicyEstimator,
     },
+    schedule::StepSize,
+    space::DiscreteSpace,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteAction)]
 enum MultiArmedBanditAction {
     Bandit1,
     Bandit2,
     Bandit3,
 }
 
-impl Action for MultiArmedBanditAction {}
-
-impl DiscreteAction for MultiArmedBanditAction {
-    const ACTIONS: &'static [Self] = &[Self::Bandit1, Self::Bandit2, Self::Bandit3];
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteObservation)]
 enum MultiArmedBanditObservation {
     Game,
 }
 
-impl Observation for MultiArmedBanditObservation {}
-
-impl DiscreteObservation for MultiArmedBanditObservation {
-    const OBSERVATIONS: &'static [Self] = &[Self::Game];
-}
+struct Cassino<AG = Player>(bool, Xoshiro256PlusPlus, PhantomData<AG>);
 
-struct Cassino(bool);
-
-impl Environment for Cassino {
-    type Agent = Player;
+impl<AG: Agent<Action = MultiArmedBanditAction, Observation = MultiArmedBanditObservation>>
+    Environment for Cassino<AG>
+{
+    type Agent = AG;
 
     fn get_observation(
         &mut self,
@@ -65,12 +61,14 @@ impl Environment for Cassino {
             MultiArmedBanditAction::Bandit2 => 2.,
             MultiArmedBanditAction::Bandit3 => 3.,
         };
-        let rand_value: f64 = rand::random();
+        let rand_value = self.1.random();
         bandit_payout_multiplier * (rand_value * 2.)
     }
 }
 
-impl EpisodicEnvironment for Cassino {
+impl<AG: Agent<Action = MultiArmedBanditAction, Observation = MultiArmedBanditObservation>>
+    EpisodicEnvironment for Cassino<AG>
+{
     fn reset_environment(&mut self) {
         self.0 = false;
     }
@@ -80,7 +78,21 @@ impl EpisodicEnvironment for Cassino {
     }
 }
 
-struct Player(EpsilonGreedyPolicy<MultiArmedBanditAction, MultiArmedBanditObservation, RandFacade>);
+impl<AG: Agent<Action = MultiArmedBanditAction, Observation = MultiArmedBanditObservation>>
+    DiscreteEnvironment for Cassino<AG>
+{
+    fn observation_space(&self) -> DiscreteSpace<MultiArmedBanditObservation> {
+        DiscreteSpace::observations()
+    }
+
+    fn action_space(&self) -> DiscreteSpace<MultiArmedBanditAction> {
+        DiscreteSpace::actions()
+    }
+}
+
+struct Player(
+    EpsilonGreedyPolicy<MultiArmedBanditAction, MultiArmedBanditObservation, Xoshiro256PlusPlus>,
+);
 
 impl Agent for Player {
     type Action = MultiArmedBanditAction;
@@ -90,35 +102,54 @@ impl Agent for Player {
         self.0.act(observation)
     }
 
-    fn policy_improvemnt(
-        &mut self,
-        action: &Self::Action,
-        observation: &Self::Observation,
-        value: f64,
-    ) {
-        self.0.policy_improvemnt(action, observation, value);
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.0.policy_improvemnt(observation, action_values);
     }
 }
 
-impl DiscreteAgent<MultiArmedBanditAction, MultiArmedBanditObservation> for Player {
-    fn action_probability(
-        &self,
-        action: &MultiArmedBanditAction,
-        observation: &MultiArmedBanditObservation,
-    ) -> f64 {
-        if self.act(observation).eq(action) {
-            1.
-        } else {
-            0.
-        }
+impl PolicyAgent for Player {
+    type Policy = EpsilonGreedyPolicy<
+        MultiArmedBanditAction,
+        MultiArmedBanditObservation,
+        Xoshiro256PlusPlus,
+    >;
+
+    fn policy(&self) -> &Self::Policy {
+        &self.0
     }
 }
 
-struct RandFacade;
+/// Plays the bandit with the highest upper confidence bound on its payout.
+struct UcbPlayer(UcbPolicy<MultiArmedBanditAction, MultiArmedBanditObservation>);
 
-impl RandomNumberGeneratorFacade for RandFacade {
-    fn random(&self) -> f64 {
-        rand::random()
+impl Agent for UcbPlayer {
+    type Action = MultiArmedBanditAction;
+    type Observation = MultiArmedBanditObservation;
+
+    fn act(&self, observation: &Self::Observation) -> Self::Action {
+        self.0.act(observation)
+    }
+
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.0.policy_improvemnt(observation, action_values);
+    }
+
+    fn policy_improvement_with_visits(
+        &mut self,
+        observation: &Self::Observation,
+        action_values: &[f64],
+        visits: &[usize],
+    ) {
+        self.0
+            .policy_improvement_with_visits(observation, action_values, visits);
+    }
+}
+
+impl PolicyAgent for UcbPlayer {
+    type Policy = UcbPolicy<MultiArmedBanditAction, MultiArmedBanditObservation>;
+
+    fn policy(&self) -> &Self::Policy {
+        &self.0
     }
 }
 
@@ -127,40 +158,79 @@ fn main() {
     const RETURN_DISCOUNT: f64 = 1.;
     const EPSILON: f64 = 0.05;
     const ALPHA: f64 = 0.05;
+    const EXPLORATION: f64 = 2.;
+    const SEED: u64 = 42;
+
+    let rng = Xoshiro256PlusPlus::from_seed(SEED);
 
-    let mut cassino = Cassino(false);
+    let mut cassino = Cassino(false, rng.fork(), PhantomData);
 
     println!("First Visit Monte Carlo");
-    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    FirstVisitMonteCarlo::<Cassino>::new(RETURN_DISCOUNT, EPISODES)
+    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = FirstVisitMonteCarlo::<Cassino>::new(RETURN_DISCOUNT, EPISODES)
         .policy_search(&mut cassino, &mut agent);
+    print!("{report}");
 
     println!("Every Visit Monte Carlo");
-    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    EveryVisitMonteCarlo::<Cassino>::new(RETURN_DISCOUNT, EPISODES)
+    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = EveryVisitMonteCarlo::<Cassino>::new(RETURN_DISCOUNT, EPISODES)
         .policy_search(&mut cassino, &mut agent);
+    print!("{report}");
 
     println!("Incremental Monte Carlo");
-    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    IncrementalMonteCarlo::<Cassino>::new(RETURN_DISCOUNT, EPISODES)
+    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = IncrementalMonteCarlo::<Cassino>::new(RETURN_DISCOUNT, EPISODES)
         .policy_search(&mut cassino, &mut agent);
+    print!("{report}");
 
     println!("Constant Alpha Monte Carlo");
-    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    ConstantAlphaMonteCarlo::<Cassino>::new(ALPHA, RETURN_DISCOUNT, EPISODES)
+    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = ConstantAlphaMonteCarlo::<Cassino>::new(ALPHA, RETURN_DISCOUNT, EPISODES)
         .policy_search(&mut cassino, &mut agent);
+    print!("{report}");
 
     println!("Q-Learning");
-    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    QLearning::<Cassino>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = QLearning::<Cassino>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
         .policy_search(&mut cassino, &mut agent);
+    print!("{report}");
+
+    println!("Q-Learning with unbiased constant step size");
+    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = QLearning::<Cassino>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .with_step_size(StepSize::UnbiasedConstant(ALPHA))
+        .policy_search(&mut cassino, &mut agent);
+    print!("{report}");
 
     println!("SARSA");
-    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    SARSA::<Cassino>::new(EPISODES, ALPHA, RETURN_DISCOUNT).policy_search(&mut cassino, &mut agent);
+    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = SARSA::<Cassino>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .policy_search(&mut cassino, &mut agent);
+    print!("{report}");
 
     println!("ExpectedSARSA");
-    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, RandFacade).unwrap());
-    ExpectedSARSA::<Cassino>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = ExpectedSARSA::<Cassino>::new(EPISODES, ALPHA, RETURN_DISCOUNT)
+        .policy_search(&mut cassino, &mut agent);
+    print!("{report}");
+
+    println!("Double Q-Learning");
+    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report = DoubleQLearning::<Cassino, _>::new(EPISODES, ALPHA, RETURN_DISCOUNT, rng.fork())
         .policy_search(&mut cassino, &mut agent);
+    print!("{report}");
+
+    println!("Double ExpectedSARSA");
+    let mut agent = Player(EpsilonGreedyPolicy::new(EPSILON, rng.fork()).unwrap());
+    let report =
+        DoubleExpectedSARSA::<Cassino, _>::new(EPISODES, ALPHA, RETURN_DISCOUNT, rng.fork())
+            .policy_search(&mut cassino, &mut agent);
+    print!("{report}");
+
+    println!("Incremental Monte Carlo with UCB");
+    let mut ucb_cassino = Cassino(false, rng.fork(), PhantomData);
+    let mut agent = UcbPlayer(UcbPolicy::new(EXPLORATION).unwrap());
+    let report = IncrementalMonteCarlo::<Cassino<UcbPlayer>>::new(RETURN_DISCOUNT, EPISODES)
+        .policy_search(&mut ucb_cassino, &mut agent);
+    print!("{report}");
 }
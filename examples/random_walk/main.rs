@@ -0,0 +1,149 @@
+use amnesia::{
+    action::DiscreteAction,
+    agent::{Agent, DiscreteAgent},
+    model::{TabularMdpEnvironment, TabularModel},
+    observation::DiscreteObservation,
+    random_number_generator::{RandomNumberGeneratorFacade, Xoshiro256PlusPlus},
+    reinforcement_learning::{
+        dynamic_programming::PolicyEvaluation,
+        prediction::{
+            EveryVisitMonteCarloPrediction, FirstVisitMonteCarloPrediction,
+            NStepTemporalDifferencePrediction, TemporalDifferenceLambdaPrediction,
+            TemporalDifferencePrediction, ValueEstimator,
+        },
+        temporal_difference::TraceType,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteAction)]
+enum Step {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteObservation)]
+enum Position {
+    LeftEnd,
+    A,
+    B,
+    C,
+    D,
+    E,
+    RightEnd,
+}
+
+/// Walks left or right with equal probability.
+struct Walker(Xoshiro256PlusPlus);
+
+impl Agent for Walker {
+    type Action = Step;
+    type Observation = Position;
+
+    fn act(&self, _observation: &Self::Observation) -> Self::Action {
+        Step::from_index(self.0.random_range(0..Step::COUNT))
+    }
+
+    fn policy_improvemnt(&mut self, _observation: &Self::Observation, _action_values: &[f64]) {
+        unreachable!("The policy of the walker is fixed.");
+    }
+}
+
+impl DiscreteAgent<Step, Position> for Walker {
+    fn action_probability(&self, _action: &Step, _observation: &Position) -> f64 {
+        1. / Step::COUNT as f64
+    }
+}
+
+/// The walk ends on either end, with a reward of 1 on the right end.
+struct RandomWalk;
+
+impl TabularModel<Position, Step> for RandomWalk {
+    fn transitions(
+        &self,
+        observation: &Position,
+        action: &Step,
+    ) -> impl Iterator<Item = (f64, Position, f64, bool)> {
+        let next_position = match (observation, action) {
+            (Position::LeftEnd | Position::RightEnd, _) => None,
+            (position, Step::Left) => Some(Position::from_index(position.index() - 1)),
+            (position, Step::Right) => Some(Position::from_index(position.index() + 1)),
+        };
+        next_position
+            .map(|next_position| {
+                (
+                    1.,
+                    next_position,
+                    if next_position == Position::RightEnd {
+                        1.
+                    } else {
+                        0.
+                    },
+                    matches!(next_position, Position::LeftEnd | Position::RightEnd),
+                )
+            })
+            .into_iter()
+    }
+}
+
+type RandomWalkEnvironment = TabularMdpEnvironment<RandomWalk, Walker, Xoshiro256PlusPlus>;
+
+fn main() {
+    const EPISODES: usize = 10000;
+    const RETURN_DISCOUNT: f64 = 1.;
+    const ALPHA: f64 = 0.05;
+    const LAMBDA: f64 = 0.8;
+    const SEED: u64 = 42;
+
+    let rng = Xoshiro256PlusPlus::from_seed(SEED);
+
+    let mut random_walk = RandomWalkEnvironment::new(RandomWalk, &[(1., Position::C)], rng.fork())
+        .expect("The random walk is a valid model.");
+    let walker = Walker(rng.fork());
+
+    println!("Policy Evaluation");
+    let report =
+        PolicyEvaluation::new(RETURN_DISCOUNT, 1e-12, 10000).evaluate(random_walk.model(), &walker);
+    print!("{}", report.state_values);
+
+    println!("TD(0)");
+    let report = TemporalDifferencePrediction::<RandomWalkEnvironment>::new(
+        EPISODES,
+        ALPHA,
+        RETURN_DISCOUNT,
+    )
+    .value_prediction(&mut random_walk, &walker);
+    print!("{report}");
+
+    println!("4-step TD");
+    let report = NStepTemporalDifferencePrediction::<RandomWalkEnvironment>::new(
+        EPISODES,
+        4,
+        ALPHA,
+        RETURN_DISCOUNT,
+    )
+    .value_prediction(&mut random_walk, &walker);
+    print!("{report}");
+
+    println!("TD(λ)");
+    let report = TemporalDifferenceLambdaPrediction::<RandomWalkEnvironment>::new(
+        EPISODES,
+        LAMBDA,
+        ALPHA,
+        RETURN_DISCOUNT,
+        TraceType::Replacing,
+    )
+    .value_prediction(&mut random_walk, &walker);
+    print!("{report}");
+
+    println!("First Visit Monte Carlo");
+    let report =
+        FirstVisitMonteCarloPrediction::<RandomWalkEnvironment>::new(RETURN_DISCOUNT, EPISODES)
+            .value_prediction(&mut random_walk, &walker);
+    print!("{report}");
+
+    println!("Every Visit Monte Carlo");
+    let report =
+        EveryVisitMonteCarloPrediction::<RandomWalkEnvironment>::new(RETURN_DISCOUNT, EPISODES)
+            .value_prediction(&mut random_walk, &walker);
+    print!("{report}");
+}
@@ -1,5 +1,8 @@
 use std::{fmt::Debug, hash::Hash};
 
+#[cfg(feature = "derive")]
+pub use amnesia_derive::DiscreteAction;
+
 /// Trait that defines the [Action]s that an [Agent] can take.
 pub trait Action
 where
@@ -14,7 +17,33 @@ where
 {
     const ACTIONS: &'static [Self];
 
-    fn index(&self) -> usize {
+    /// Number of [`DiscreteAction`]s.
+    const COUNT: usize = Self::ACTIONS.len();
+
+    /// Position of the [`DiscreteAction`] on [`DiscreteAction::ACTIONS`].
+    ///
+    /// Estimators call this on every update, so it should run in constant time.
+    /// [`DiscreteAction::search_index`] can be used for small sets of actions.
+    fn index(&self) -> usize;
+
+    /// Inverse of [`DiscreteAction::index`].
+    fn from_index(index: usize) -> Self {
+        Self::ACTIONS[index]
+    }
+
+    /// Same as [`DiscreteAction::index`], but on debug builds checks that the index
+    /// points to the [`DiscreteAction`] on [`DiscreteAction::ACTIONS`].
+    fn checked_index(&self) -> usize {
+        let index = self.index();
+        debug_assert!(
+            Self::ACTIONS.get(index) == Some(self),
+            "Index of {self:?} must point to it in Discrete actions."
+        );
+        index
+    }
+
+    /// Finds the position of the [`DiscreteAction`] by searching [`DiscreteAction::ACTIONS`].
+    fn search_index(&self) -> usize {
         Self::ACTIONS
             .iter()
             .position(|action| action.eq(self))
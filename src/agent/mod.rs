@@ -1,4 +1,11 @@
-use crate::{action::DiscreteAction, observation::DiscreteObservation};
+use crate::{
+    policy::{Policy, StochasticPolicy},
+    space::{DiscreteSpace, SpaceElement},
+};
+
+// Docs imports
+#[allow(unused_imports)]
+use crate::{environment::DiscreteEnvironment, policy::ucb::UcbPolicy};
 
 pub trait Agent
 where
@@ -9,16 +16,71 @@ where
 
     fn act(&self, observation: &Self::Observation) -> Self::Action;
 
-    fn policy_improvemnt(
+    /// Receives the values of all the actions of `observation`, in the order of the
+    /// action [`DiscreteSpace`] of the [`DiscreteEnvironment`].
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]);
+
+    /// Same as [`Agent::policy_improvemnt`], but also informs `visits`, the number of
+    /// times that the estimator updated the value of each action. Needed by policies
+    /// that explore based on the visits, like [`UcbPolicy`].
+    fn policy_improvement_with_visits(
         &mut self,
-        action: &Self::Action,
         observation: &Self::Observation,
-        value: f64,
-    );
+        action_values: &[f64],
+        _visits: &[usize],
+    ) {
+        self.policy_improvemnt(observation, action_values);
+    }
+
+    /// Called by the estimators at the end of every episode. Agents should forward
+    /// it to their [Policy] if it anneals its exploration.
+    fn on_episode_end(&mut self) {}
+
+    /// Called by the estimators after every action taken. Agents should forward it
+    /// to their [Policy] if it anneals its exploration.
+    fn on_step_end(&mut self) {}
 }
 
-pub trait DiscreteAgent<AC: DiscreteAction, S: DiscreteObservation>:
+pub trait DiscreteAgent<AC: SpaceElement, S: SpaceElement>:
     Agent<Action = AC, Observation = S>
 {
     fn action_probability(&self, action: &AC, observation: &S) -> f64;
+
+    /// Probability of each action of `action_space` on `observation`, in its order.
+    fn action_distribution(&self, observation: &S, action_space: &DiscreteSpace<AC>) -> Vec<f64> {
+        action_space
+            .iter()
+            .map(|action| self.action_probability(&action, observation))
+            .collect()
+    }
+}
+
+/// An [Agent] that acts with a [Policy].
+///
+/// Agents whose [Policy] is a [`StochasticPolicy`] implement [`DiscreteAgent`] with
+/// the probabilities of the [Policy].
+pub trait PolicyAgent: Agent {
+    type Policy: Policy<Action = Self::Action, Observation = Self::Observation>;
+
+    fn policy(&self) -> &Self::Policy;
+}
+
+impl<AC: SpaceElement, S: SpaceElement, AG: PolicyAgent<Action = AC, Observation = S>>
+    DiscreteAgent<AC, S> for AG
+where
+    AG::Policy: StochasticPolicy<AC, S>,
+{
+    fn action_probability(&self, action: &AC, observation: &S) -> f64 {
+        self.policy().action_probability(action, observation)
+    }
+
+    /// The distribution of the [Policy], which must be over `action_space`.
+    fn action_distribution(&self, observation: &S, action_space: &DiscreteSpace<AC>) -> Vec<f64> {
+        debug_assert_eq!(
+            self.policy().action_space().len(),
+            action_space.len(),
+            "The policy must be over the action space."
+        );
+        self.policy().action_distribution(observation)
+    }
 }
@@ -1,8 +1,11 @@
-use crate::agent::Agent;
+use crate::{
+    agent::Agent,
+    space::{ActionMask, DiscreteSpace, SpaceElement},
+};
 
 // Docs imports
 #[allow(unused_imports)]
-use crate::observation::Observation;
+use crate::{action::Action, observation::Observation};
 
 /// [Environment] is an interface between the world and the [Agent].  
 ///
@@ -26,10 +29,69 @@ pub trait Environment {
     ) -> f64;
 }
 
+/// An [Environment] whose [Observation]s and [Action]s are the members of
+/// [`DiscreteSpace`]s, which tabular estimators use to size their tables.
+///
+/// Environments of [`DiscreteObservation`](crate::observation::DiscreteObservation)s
+/// and [`DiscreteAction`](crate::action::DiscreteAction)s can return
+/// [`DiscreteSpace::observations`] and [`DiscreteSpace::actions`].
+pub trait DiscreteEnvironment: Environment
+where
+    <Self::Agent as Agent>::Observation: SpaceElement,
+    <Self::Agent as Agent>::Action: SpaceElement,
+{
+    /// Every [Observation] that the [Agent] can receive.
+    fn observation_space(&self) -> DiscreteSpace<<Self::Agent as Agent>::Observation>;
+
+    /// Every [Action] that the [Agent] can take.
+    fn action_space(&self) -> DiscreteSpace<<Self::Agent as Agent>::Action>;
+
+    /// The [Action]s of the action space that are legal on each [Observation].
+    /// Every action is legal by default.
+    ///
+    /// Estimators only bootstrap from the legal actions. Agents should give the
+    /// same mask to their policies, so they only take legal actions.
+    fn action_mask(
+        &self,
+    ) -> ActionMask<<Self::Agent as Agent>::Observation, <Self::Agent as Agent>::Action> {
+        ActionMask::all(self.action_space())
+    }
+}
+
+/// How an episode of an [`EpisodicEnvironment`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpisodeEnd {
+    /// The episode reached a terminal state, which has no future rewards.
+    Terminated,
+    /// The episode was cut short, e.g. by a time limit, on a state that could
+    /// still have future rewards.
+    Truncated,
+}
+
 pub trait EpisodicEnvironment: Environment {
     /// Reset [`EpisodicEnvironment`] to an initial state.
     fn reset_environment(&mut self);
 
     /// Get the [Observation] of the terminal state of the [`EpisodicEnvironment`].
     fn final_observation(&self, agent: &Self::Agent) -> <Self::Agent as Agent>::Observation;
+
+    /// How the episode ended, called once [`Environment::get_observation`] returns `None`.
+    ///
+    /// Estimators bootstrap from the value of [`EpisodicEnvironment::final_observation`]
+    /// when the episode was [`EpisodeEnd::Truncated`], instead of assuming no future rewards.
+    fn episode_end(&self) -> EpisodeEnd {
+        EpisodeEnd::Terminated
+    }
+}
+
+/// An [`EpisodicEnvironment`] that can start an episode on any [Observation].
+///
+/// Needed by estimators with exploring starts, which sample every observation
+/// as a starting point regardless of the usual initial states.
+pub trait ExploringStartsEnvironment: EpisodicEnvironment {
+    /// Reset [`ExploringStartsEnvironment`] to a state where the [Agent] observes `observation`.
+    ///
+    /// Returns `false` if the episode is already over on `observation`, like on terminal
+    /// ones, so the [Agent] can't act.
+    fn reset_to(&mut self, observation: &<Self::Agent as Agent>::Observation) -> bool;
 }
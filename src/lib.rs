@@ -1,8 +1,12 @@
 pub mod action;
 pub mod agent;
 pub mod environment;
+pub mod model;
 pub mod observation;
 pub mod policy;
 pub mod random_number_generator;
 pub mod reinforcement_learning;
+pub mod schedule;
+pub mod space;
 pub mod trajectory;
+pub mod value_table;
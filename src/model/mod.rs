@@ -0,0 +1,25 @@
+use crate::{action::DiscreteAction, observation::DiscreteObservation};
+
+mod tabular_mdp_environment;
+
+pub use self::tabular_mdp_environment::{TabularMdpEnvironment, TabularMdpEnvironmentError};
+
+// Docs imports
+#[allow(unused_imports)]
+use crate::environment::Environment;
+
+/// A complete description of the dynamics of an [Environment] with discrete
+/// observations and actions.
+///
+/// The observations are assumed to be the states of the [Environment], that is,
+/// the model is a Markov Decision Process.
+pub trait TabularModel<S: DiscreteObservation, A: DiscreteAction> {
+    /// All possible outcomes of taking `action` on `observation`, as tuples of
+    /// `(probability, next_observation, reward, terminal)`, where `terminal` is
+    /// `true` if the episode ends after the transition.
+    ///
+    /// The probabilities of the outcomes should sum to 1. Observations from which
+    /// no action can be taken, like terminal ones, may have no transitions.
+    fn transitions(&self, observation: &S, action: &A)
+        -> impl Iterator<Item = (f64, S, f64, bool)>;
+}
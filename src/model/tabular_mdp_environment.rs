@@ -0,0 +1,279 @@
+use std::{error::Error, fmt::Display, marker::PhantomData};
+
+use crate::{
+    action::DiscreteAction,
+    agent::Agent,
+    environment::{
+        DiscreteEnvironment, Environment, EpisodicEnvironment, ExploringStartsEnvironment,
+    },
+    model::TabularModel,
+    observation::DiscreteObservation,
+    random_number_generator::RandomNumberGeneratorFacade,
+    space::DiscreteSpace,
+    value_table::ObservationActionTable,
+};
+
+/// How far from 1 a sum of probabilities can be before it is considered invalid.
+const PROBABILITY_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug)]
+pub enum TabularMdpEnvironmentError<S, AC> {
+    /// A probability is negative or not a number.
+    InvalidProbability,
+    /// The probabilities of the initial observations sum to the value instead of 1.
+    InitialDistributionNotNormalized(f64),
+    /// The probabilities of the transitions of the pair sum to `total` instead of 1.
+    TransitionsNotNormalized {
+        observation: S,
+        action: AC,
+        total: f64,
+    },
+    /// The pair has no transitions, but `observation` can be reached without ending the episode.
+    MissingTransitions { observation: S, action: AC },
+}
+
+impl<S: DiscreteObservation, AC: DiscreteAction> Display for TabularMdpEnvironmentError<S, AC> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let m = match self {
+            Self::InvalidProbability => "Probabilities must be non negative numbers.".to_string(),
+            Self::InitialDistributionNotNormalized(total) => {
+                format!("Initial observation probabilities must sum to 1, but sum to {total}.")
+            }
+            Self::TransitionsNotNormalized {
+                observation,
+                action,
+                total,
+            } => format!(
+                "Transition probabilities of ({observation:?}, {action:?}) must sum to 1, but sum to {total}."
+            ),
+            Self::MissingTransitions {
+                observation,
+                action,
+            } => format!(
+                "({observation:?}, {action:?}) must have transitions, since {observation:?} is not terminal."
+            ),
+        };
+        write!(f, "{m}")
+    }
+}
+
+impl<S: DiscreteObservation, AC: DiscreteAction> Error for TabularMdpEnvironmentError<S, AC> {}
+
+/// An [`EpisodicEnvironment`] that samples the transitions of a [`TabularModel`].
+///
+/// Episodes start on an observation sampled from the initial distribution and end
+/// after a transition marked as terminal.
+pub struct TabularMdpEnvironment<M, AG: Agent, RNG: RandomNumberGeneratorFacade> {
+    model: M,
+    initial_distribution: Vec<f64>,
+    random_number_generator: RNG,
+    observation: AG::Observation,
+    terminated: bool,
+    phantom_agent: PhantomData<AG>,
+}
+
+impl<
+        AC: DiscreteAction,
+        S: DiscreteObservation,
+        AG: Agent<Action = AC, Observation = S>,
+        M: TabularModel<S, AC>,
+        RNG: RandomNumberGeneratorFacade,
+    > TabularMdpEnvironment<M, AG, RNG>
+{
+    /// # Arguments
+    /// `initial_distribution`: Pairs of `(probability, observation)` to start the episodes on</br>
+    /// `random_number_generator`: Samples the initial observations and the transitions
+    /// # Errors
+    /// Fails if the probabilities of `initial_distribution` or of the transitions of
+    /// any observation-action pair don't sum to 1, or if an observation that can be
+    /// reached without ending the episode has an action without transitions.
+    pub fn new(
+        model: M,
+        initial_distribution: &[(f64, S)],
+        random_number_generator: RNG,
+    ) -> Result<Self, TabularMdpEnvironmentError<S, AC>> {
+        let mut initial_probabilities = vec![0.; S::COUNT];
+        for (probability, observation) in initial_distribution {
+            if !(0.0f64..).contains(probability) {
+                return Err(TabularMdpEnvironmentError::InvalidProbability);
+            }
+            initial_probabilities[observation.checked_index()] += probability;
+        }
+        let total: f64 = initial_probabilities.iter().sum();
+        if (total - 1.).abs() > PROBABILITY_TOLERANCE {
+            return Err(TabularMdpEnvironmentError::InitialDistributionNotNormalized(total));
+        }
+
+        Self::validate_transitions(&model, &initial_probabilities)?;
+
+        let observation =
+            S::from_index(random_number_generator.random_weighted(&initial_probabilities));
+        Ok(Self {
+            model,
+            initial_distribution: initial_probabilities,
+            random_number_generator,
+            observation,
+            terminated: false,
+            phantom_agent: PhantomData,
+        })
+    }
+
+    /// Checks that the transitions of every pair sum to 1, and that every observation
+    /// where the episode can be ongoing has transitions for all actions.
+    fn validate_transitions(
+        model: &M,
+        initial_probabilities: &[f64],
+    ) -> Result<(), TabularMdpEnvironmentError<S, AC>> {
+        let mut ongoing: Vec<bool> = initial_probabilities
+            .iter()
+            .map(|probability| *probability > 0.)
+            .collect();
+        let mut has_transitions = ObservationActionTable::<S, AC, bool>::filled(false);
+
+        for observation in S::OBSERVATIONS {
+            for action in AC::ACTIONS {
+                let mut total = 0.;
+                let mut transitions = 0usize;
+                for (probability, next_observation, _reward, terminal) in
+                    model.transitions(observation, action)
+                {
+                    if !(0.0f64..).contains(&probability) {
+                        return Err(TabularMdpEnvironmentError::InvalidProbability);
+                    }
+                    total += probability;
+                    transitions += 1;
+                    if !terminal && probability > 0. {
+                        ongoing[next_observation.checked_index()] = true;
+                    }
+                }
+                if transitions > 0 {
+                    if (total - 1.).abs() > PROBABILITY_TOLERANCE {
+                        return Err(TabularMdpEnvironmentError::TransitionsNotNormalized {
+                            observation: *observation,
+                            action: *action,
+                            total,
+                        });
+                    }
+                    has_transitions[(observation, action)] = true;
+                }
+            }
+        }
+
+        for (observation, _) in S::OBSERVATIONS
+            .iter()
+            .zip(ongoing)
+            .filter(|(_, ongoing)| *ongoing)
+        {
+            for action in AC::ACTIONS {
+                if !has_transitions[(observation, action)] {
+                    return Err(TabularMdpEnvironmentError::MissingTransitions {
+                        observation: *observation,
+                        action: *action,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The [`TabularModel`] sampled by the environment.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+}
+
+impl<
+        AC: DiscreteAction,
+        S: DiscreteObservation,
+        AG: Agent<Action = AC, Observation = S>,
+        M: TabularModel<S, AC>,
+        RNG: RandomNumberGeneratorFacade,
+    > Environment for TabularMdpEnvironment<M, AG, RNG>
+{
+    type Agent = AG;
+
+    fn get_observation(
+        &mut self,
+        _agent: &Self::Agent,
+    ) -> Option<<Self::Agent as Agent>::Observation> {
+        (!self.terminated).then_some(self.observation)
+    }
+
+    fn receive_action(
+        &mut self,
+        _agent: &Self::Agent,
+        action: &<Self::Agent as Agent>::Action,
+    ) -> f64 {
+        let transitions: Vec<(f64, S, f64, bool)> =
+            self.model.transitions(&self.observation, action).collect();
+        let probabilities: Vec<f64> = transitions
+            .iter()
+            .map(|(probability, _, _, _)| *probability)
+            .collect();
+        let (_, next_observation, reward, terminal) =
+            transitions[self.random_number_generator.random_weighted(&probabilities)];
+
+        self.observation = next_observation;
+        self.terminated = terminal;
+        reward
+    }
+}
+
+impl<
+        AC: DiscreteAction,
+        S: DiscreteObservation,
+        AG: Agent<Action = AC, Observation = S>,
+        M: TabularModel<S, AC>,
+        RNG: RandomNumberGeneratorFacade,
+    > EpisodicEnvironment for TabularMdpEnvironment<M, AG, RNG>
+{
+    fn reset_environment(&mut self) {
+        self.observation = S::from_index(
+            self.random_number_generator
+                .random_weighted(&self.initial_distribution),
+        );
+        self.terminated = false;
+    }
+
+    fn final_observation(&self, _agent: &Self::Agent) -> <Self::Agent as Agent>::Observation {
+        self.observation
+    }
+}
+
+impl<
+        AC: DiscreteAction,
+        S: DiscreteObservation,
+        AG: Agent<Action = AC, Observation = S>,
+        M: TabularModel<S, AC>,
+        RNG: RandomNumberGeneratorFacade,
+    > ExploringStartsEnvironment for TabularMdpEnvironment<M, AG, RNG>
+{
+    /// Starts the episode on `observation`, even if it isn't on the initial distribution.
+    ///
+    /// Observations that have actions without transitions, like terminal ones, start
+    /// an episode that is already over.
+    fn reset_to(&mut self, observation: &<Self::Agent as Agent>::Observation) -> bool {
+        self.observation = *observation;
+        self.terminated = AC::ACTIONS
+            .iter()
+            .any(|action| self.model.transitions(observation, action).next().is_none());
+        !self.terminated
+    }
+}
+
+impl<
+        AC: DiscreteAction,
+        S: DiscreteObservation,
+        AG: Agent<Action = AC, Observation = S>,
+        M: TabularModel<S, AC>,
+        RNG: RandomNumberGeneratorFacade,
+    > DiscreteEnvironment for TabularMdpEnvironment<M, AG, RNG>
+{
+    fn observation_space(&self) -> DiscreteSpace<S> {
+        DiscreteSpace::observations()
+    }
+
+    fn action_space(&self) -> DiscreteSpace<AC> {
+        DiscreteSpace::actions()
+    }
+}
@@ -1,5 +1,8 @@
 use std::{fmt::Debug, hash::Hash};
 
+#[cfg(feature = "derive")]
+pub use amnesia_derive::DiscreteObservation;
+
 // Docs imports
 #[allow(unused_imports)]
 use crate::{agent::Agent, environment::Environment};
@@ -21,7 +24,34 @@ where
 {
     const OBSERVATIONS: &'static [Self];
 
-    fn index(&self) -> usize {
+    /// Number of [`DiscreteObservation`]s.
+    const COUNT: usize = Self::OBSERVATIONS.len();
+
+    /// Position of the [`DiscreteObservation`] on [`DiscreteObservation::OBSERVATIONS`].
+    ///
+    /// Estimators call this on every update, so it should run in constant time.
+    /// [`DiscreteObservation::search_index`] can be used for small sets of observations.
+    fn index(&self) -> usize;
+
+    /// Inverse of [`DiscreteObservation::index`].
+    fn from_index(index: usize) -> Self {
+        Self::OBSERVATIONS[index]
+    }
+
+    /// Same as [`DiscreteObservation::index`], but on debug builds checks that the index
+    /// points to the [`DiscreteObservation`] on [`DiscreteObservation::OBSERVATIONS`].
+    fn checked_index(&self) -> usize {
+        let index = self.index();
+        debug_assert!(
+            Self::OBSERVATIONS.get(index) == Some(self),
+            "Index of {self:?} must point to it in Discrete observations."
+        );
+        index
+    }
+
+    /// Finds the position of the [`DiscreteObservation`] by searching
+    /// [`DiscreteObservation::OBSERVATIONS`].
+    fn search_index(&self) -> usize {
         Self::OBSERVATIONS
             .iter()
             .position(|observation| observation.eq(self))
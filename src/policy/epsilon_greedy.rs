@@ -1,10 +1,19 @@
-use std::{error::Error, fmt::Display, marker::PhantomData};
+use std::{error::Error, fmt::Display};
 
 use crate::{
-    action::DiscreteAction, observation::DiscreteObservation, policy::Policy,
+    action::{Action, DiscreteAction},
+    observation::{DiscreteObservation, Observation},
+    policy::{random_legal_action, random_legal_argmax, Policy, StochasticPolicy},
     random_number_generator::RandomNumberGeneratorFacade,
+    schedule::{Schedule, ScheduleTiming},
+    space::{ActionMask, DiscreteSpace, SpaceElement},
+    value_table::{Dense, Storage, StorageTable, ValueStorage},
 };
 
+// Docs imports
+#[allow(unused_imports)]
+use crate::environment::DiscreteEnvironment;
+
 #[derive(Debug)]
 pub enum EpsilonGreedyPolicyError {
     EpsilonOutOfRange,
@@ -13,7 +22,7 @@ pub enum EpsilonGreedyPolicyError {
 impl Display for EpsilonGreedyPolicyError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let m = match self {
-            Self::EpsilonOutOfRange => "Epsilon must be between [0, 1).",
+            Self::EpsilonOutOfRange => "Epsilon must always be between [0, 1).",
         };
         write!(f, "{m}")
     }
@@ -21,71 +30,190 @@ impl Display for EpsilonGreedyPolicyError {
 
 impl Error for EpsilonGreedyPolicyError {}
 
+/// Takes a random action with probability `ε`, and the action with the highest value
+/// otherwise. Observations whose values were never improved always take random actions.
+///
+/// Both the random and the greedy actions are among the legal actions of its
+/// [`ActionMask`].
+/// The greedy actions are kept in the [`Storage`] `ST`.
 pub struct EpsilonGreedyPolicy<
-    A: DiscreteAction,
-    S: DiscreteObservation,
+    A: SpaceElement,
+    S: SpaceElement,
     RNG: RandomNumberGeneratorFacade,
+    ST: Storage = Dense,
 > {
     epsilon: f64,
+    schedule: Schedule,
+    schedule_timing: ScheduleTiming,
+    /// Number of times the schedule was advanced
+    schedule_advances: usize,
     rng_facade: RNG,
-    observation_action_mapping: Vec<(A, f64)>,
-    observation_phantom: PhantomData<S>,
+    observation_space: DiscreteSpace<S>,
+    action_mask: ActionMask<S, A>,
+    greedy_actions: StorageTable<ST, Option<A>>,
 }
 
-impl<A: DiscreteAction, S: DiscreteObservation, RNG: RandomNumberGeneratorFacade>
-    EpsilonGreedyPolicy<A, S, RNG>
+impl<A: DiscreteAction, S: DiscreteObservation, RNG: RandomNumberGeneratorFacade, ST: Storage>
+    EpsilonGreedyPolicy<A, S, RNG, ST>
 {
     pub fn new(epsilon: f64, rng_facade: RNG) -> Result<Self, EpsilonGreedyPolicyError> {
-        if (0.0f64..1.0).contains(&epsilon) {
-            let random_start = S::OBSERVATIONS
-                .iter()
-                .map(|_| {
-                    (
-                        A::ACTIONS[(rng_facade.random() * A::ACTIONS.len() as f64) as usize],
-                        0.,
-                    )
-                })
-                .collect();
+        Self::with_schedule(
+            Schedule::Constant(epsilon),
+            ScheduleTiming::PerEpisode,
+            rng_facade,
+        )
+    }
+
+    /// Policy whose `ε` follows `schedule`, advanced by the estimators as given by
+    /// `schedule_timing`.
+    pub fn with_schedule(
+        schedule: Schedule,
+        schedule_timing: ScheduleTiming,
+        rng_facade: RNG,
+    ) -> Result<Self, EpsilonGreedyPolicyError> {
+        Self::with_spaces(
+            DiscreteSpace::observations(),
+            DiscreteSpace::actions(),
+            schedule,
+            schedule_timing,
+            rng_facade,
+        )
+    }
+}
+
+impl<A: SpaceElement, S: SpaceElement, RNG: RandomNumberGeneratorFacade, ST: Storage>
+    EpsilonGreedyPolicy<A, S, RNG, ST>
+{
+    /// Same as [`EpsilonGreedyPolicy::with_schedule`], but over the observations of
+    /// `observation_space` and the actions of `action_space`, usually the spaces of
+    /// the [`DiscreteEnvironment`].
+    pub fn with_spaces(
+        observation_space: DiscreteSpace<S>,
+        action_space: DiscreteSpace<A>,
+        schedule: Schedule,
+        schedule_timing: ScheduleTiming,
+        rng_facade: RNG,
+    ) -> Result<Self, EpsilonGreedyPolicyError> {
+        let within_range = schedule
+            .bounds()
+            .is_some_and(|(min, max)| (0.0f64..1.0).contains(&min) && (0.0f64..1.0).contains(&max));
+        if within_range {
             Ok(Self {
-                epsilon,
+                epsilon: schedule.value(0),
+                schedule,
+                schedule_timing,
+                schedule_advances: 0,
                 rng_facade,
-                observation_action_mapping: random_start,
-                observation_phantom: PhantomData,
+                greedy_actions: StorageTable::<ST, Option<A>>::with_default(
+                    observation_space.len(),
+                    1,
+                    None,
+                ),
+                observation_space,
+                action_mask: ActionMask::all(action_space),
             })
         } else {
             Err(EpsilonGreedyPolicyError::EpsilonOutOfRange)
         }
     }
+
+    /// Restricts the policy to the legal actions of `action_mask`, over its action space.
+    #[must_use]
+    pub fn with_action_mask(mut self, action_mask: ActionMask<S, A>) -> Self {
+        self.action_mask.replace(action_mask);
+        self
+    }
+
+    /// Current exploration rate `ε`.
+    #[must_use]
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    fn advance_schedule(&mut self, timing: ScheduleTiming) {
+        if self.schedule_timing == timing {
+            self.schedule_advances += 1;
+            self.epsilon = self.schedule.value(self.schedule_advances);
+        }
+    }
+
+    /// Probability of `action` on `observation`, which has `legal_actions` legal actions.
+    fn legal_probability(&self, action: &A, observation: &S, legal_actions: usize) -> f64 {
+        if !self.action_mask.is_legal(observation, action) {
+            return 0.;
+        }
+        let uniform = 1. / legal_actions as f64;
+        let exploration = self.epsilon * uniform;
+        match self.greedy_actions[self.observation_space.index_of(observation)] {
+            Some(greedy_action) if greedy_action.eq(action) => exploration + 1. - self.epsilon,
+            Some(_) => exploration,
+            None => uniform,
+        }
+    }
 }
 
-impl<A: DiscreteAction, S: DiscreteObservation, RNG: RandomNumberGeneratorFacade> Policy
-    for EpsilonGreedyPolicy<A, S, RNG>
+impl<
+        A: Action + SpaceElement,
+        S: Observation + SpaceElement,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > Policy for EpsilonGreedyPolicy<A, S, RNG, ST>
 {
     type Observation = S;
     type Action = A;
 
     fn act(&self, observation: &Self::Observation) -> Self::Action {
-        if self.rng_facade.random().lt(&self.epsilon) {
-            A::ACTIONS[(self.rng_facade.random() * A::ACTIONS.len() as f64) as usize]
-        } else {
-            self.observation_action_mapping[observation.index()].0
+        match self.greedy_actions[self.observation_space.index_of(observation)] {
+            Some(greedy_action) if self.rng_facade.random().ge(&self.epsilon) => greedy_action,
+            _ => random_legal_action(&self.action_mask, observation, &self.rng_facade),
         }
     }
 
-    fn policy_improvemnt(
-        &mut self,
-        action: &Self::Action,
-        observation: &Self::Observation,
-        value: f64,
-    ) {
-        let observation_index = observation.index();
-        if self.observation_action_mapping[observation_index]
-            .0
-            .eq(action)
-        {
-            self.observation_action_mapping[observation_index].1 = value;
-        } else if self.observation_action_mapping[observation_index].1 < value {
-            self.observation_action_mapping[observation_index] = (*action, value);
-        }
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.greedy_actions[self.observation_space.index_of(observation)] =
+            Some(random_legal_argmax(
+                &self.action_mask,
+                observation,
+                action_values,
+                &self.rng_facade,
+            ));
+    }
+
+    fn on_episode_end(&mut self) {
+        self.advance_schedule(ScheduleTiming::PerEpisode);
+    }
+
+    fn on_step_end(&mut self) {
+        self.advance_schedule(ScheduleTiming::PerStep);
+    }
+}
+
+/// Explores uniformly with probability `ε`, so every legal action has `ε / |A(s)|`
+/// probability plus `1 - ε` on the greedy action, where `A(s)` are the legal actions
+/// of the observation.
+impl<
+        A: Action + SpaceElement,
+        S: Observation + SpaceElement,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > StochasticPolicy<A, S> for EpsilonGreedyPolicy<A, S, RNG, ST>
+{
+    fn action_probability(&self, action: &A, observation: &S) -> f64 {
+        let legal_actions = self.action_mask.legal_actions(observation).count();
+        self.legal_probability(action, observation, legal_actions)
+    }
+
+    /// Counts the legal actions once for the whole distribution.
+    fn action_distribution(&self, observation: &S) -> Vec<f64> {
+        let legal_actions = self.action_mask.legal_actions(observation).count();
+        self.action_mask
+            .action_space()
+            .iter()
+            .map(|action| self.legal_probability(&action, observation, legal_actions))
+            .collect()
+    }
+
+    fn action_space(&self) -> &DiscreteSpace<A> {
+        self.action_mask.action_space()
     }
 }
@@ -1,76 +1,230 @@
-use std::marker::PhantomData;
-
 use crate::{
-    action::DiscreteAction, observation::DiscreteObservation,
+    action::{Action, DiscreteAction},
+    observation::{DiscreteObservation, Observation},
     random_number_generator::RandomNumberGeneratorFacade,
+    space::{ActionMask, DiscreteSpace, SpaceElement},
+    value_table::{Dense, Storage, StorageTable, ValueStorage},
 };
 
+// Docs imports
+#[allow(unused_imports)]
+use crate::environment::DiscreteEnvironment;
+
 pub mod epsilon_greedy;
+pub mod softmax;
+pub mod ucb;
 
 pub trait Policy
 where
     Self: Sized,
 {
-    type Action: crate::action::Action;
-    type Observation: crate::observation::Observation;
+    type Action: Action;
+    type Observation: Observation;
 
     fn act(&self, observation: &Self::Observation) -> Self::Action;
 
-    fn policy_improvemnt(
+    /// Receives the values of all the actions of `observation`, in the order of the
+    /// action [`DiscreteSpace`] of the [`DiscreteEnvironment`].
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]);
+
+    /// Same as [`Policy::policy_improvemnt`], but also informs `visits`, the number of
+    /// times that the estimator updated the value of each action. Needed by policies
+    /// that explore based on the visits, like [`UcbPolicy`](ucb::UcbPolicy).
+    fn policy_improvement_with_visits(
         &mut self,
-        action: &Self::Action,
         observation: &Self::Observation,
-        value: f64,
-    );
+        action_values: &[f64],
+        _visits: &[usize],
+    ) {
+        self.policy_improvemnt(observation, action_values);
+    }
+
+    /// Called by the estimators at the end of every episode, so the [Policy] can
+    /// anneal its exploration.
+    fn on_episode_end(&mut self) {}
+
+    /// Called by the estimators after every action taken by the agent, so the
+    /// [Policy] can anneal its exploration.
+    fn on_step_end(&mut self) {}
 }
 
-pub struct Greedy<A: DiscreteAction, S: DiscreteObservation> {
-    observation_action_mapping: Vec<(A, f64)>,
-    observation_phantom: PhantomData<S>,
+/// A [Policy] over a [`DiscreteSpace`] of actions that knows the probability of taking
+/// each action.
+pub trait StochasticPolicy<A: SpaceElement, S: SpaceElement>:
+    Policy<Action = A, Observation = S>
+{
+    /// Probability of [`Policy::act`] returning `action` on `observation`.
+    fn action_probability(&self, action: &A, observation: &S) -> f64;
+
+    /// The [`DiscreteSpace`] of the actions that the policy takes.
+    fn action_space(&self) -> &DiscreteSpace<A>;
+
+    /// Probability of each action on `observation`, in the order of
+    /// [`StochasticPolicy::action_space`].
+    fn action_distribution(&self, observation: &S) -> Vec<f64> {
+        self.action_space()
+            .iter()
+            .map(|action| self.action_probability(&action, observation))
+            .collect()
+    }
 }
 
-impl<A: DiscreteAction, S: DiscreteObservation> Greedy<A, S> {
+/// Takes the action with the highest value, breaking ties uniformly at random
+/// whenever the values of an observation are improved.
+///
+/// Observations whose values were never improved take actions uniformly at random.
+/// Only the legal actions of its [`ActionMask`] are ever taken.
+/// The greedy actions are kept in the [`Storage`] `ST`.
+pub struct Greedy<
+    A: SpaceElement,
+    S: SpaceElement,
+    RNG: RandomNumberGeneratorFacade,
+    ST: Storage = Dense,
+> {
+    rng_facade: RNG,
+    observation_space: DiscreteSpace<S>,
+    action_mask: ActionMask<S, A>,
+    greedy_actions: StorageTable<ST, Option<A>>,
+}
+
+impl<A: DiscreteAction, S: DiscreteObservation, RNG: RandomNumberGeneratorFacade, ST: Storage>
+    Greedy<A, S, RNG, ST>
+{
     #[must_use]
-    pub fn new(start_state_randomizer: &dyn RandomNumberGeneratorFacade) -> Self {
-        let random_start = S::OBSERVATIONS
-            .iter()
-            .map(|_| {
-                (
-                    A::ACTIONS
-                        [(start_state_randomizer.random() * A::ACTIONS.len() as f64) as usize],
-                    0.,
-                )
-            })
-            .collect();
+    pub fn new(rng_facade: RNG) -> Self {
+        Self::with_spaces(
+            DiscreteSpace::observations(),
+            DiscreteSpace::actions(),
+            rng_facade,
+        )
+    }
+}
+
+impl<A: SpaceElement, S: SpaceElement, RNG: RandomNumberGeneratorFacade, ST: Storage>
+    Greedy<A, S, RNG, ST>
+{
+    /// Policy over the observations of `observation_space` and the actions of
+    /// `action_space`, usually the spaces of the [`DiscreteEnvironment`].
+    #[must_use]
+    pub fn with_spaces(
+        observation_space: DiscreteSpace<S>,
+        action_space: DiscreteSpace<A>,
+        rng_facade: RNG,
+    ) -> Self {
         Self {
-            observation_action_mapping: random_start,
-            observation_phantom: PhantomData,
+            rng_facade,
+            greedy_actions: StorageTable::<ST, Option<A>>::with_default(
+                observation_space.len(),
+                1,
+                None,
+            ),
+            observation_space,
+            action_mask: ActionMask::all(action_space),
         }
     }
+
+    /// Restricts the policy to the legal actions of `action_mask`, over its action space.
+    #[must_use]
+    pub fn with_action_mask(mut self, action_mask: ActionMask<S, A>) -> Self {
+        self.action_mask.replace(action_mask);
+        self
+    }
 }
 
-impl<A: DiscreteAction, S: DiscreteObservation> Policy for Greedy<A, S> {
+impl<
+        A: Action + SpaceElement,
+        S: Observation + SpaceElement,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > Policy for Greedy<A, S, RNG, ST>
+{
     type Action = A;
     type Observation = S;
 
     fn act(&self, observation: &Self::Observation) -> Self::Action {
-        self.observation_action_mapping[observation.index()].0
+        self.greedy_actions[self.observation_space.index_of(observation)].unwrap_or_else(|| {
+            random_legal_action(&self.action_mask, observation, &self.rng_facade)
+        })
     }
 
-    fn policy_improvemnt(
-        &mut self,
-        action: &Self::Action,
-        observation: &Self::Observation,
-        value: f64,
-    ) {
-        let observation_index = observation.index();
-        if self.observation_action_mapping[observation_index]
-            .0
-            .eq(action)
-        {
-            self.observation_action_mapping[observation_index].1 = value;
-        } else if self.observation_action_mapping[observation_index].1 < value {
-            self.observation_action_mapping[observation_index] = (*action, value);
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.greedy_actions[self.observation_space.index_of(observation)] =
+            Some(random_legal_argmax(
+                &self.action_mask,
+                observation,
+                action_values,
+                &self.rng_facade,
+            ));
+    }
+}
+
+impl<
+        A: Action + SpaceElement,
+        S: Observation + SpaceElement,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > StochasticPolicy<A, S> for Greedy<A, S, RNG, ST>
+{
+    fn action_probability(&self, action: &A, observation: &S) -> f64 {
+        if !self.action_mask.is_legal(observation, action) {
+            return 0.;
+        }
+        match self.greedy_actions[self.observation_space.index_of(observation)] {
+            Some(greedy_action) if greedy_action.eq(action) => 1.,
+            Some(_) => 0.,
+            None => 1. / self.action_mask.legal_actions(observation).count() as f64,
         }
     }
+
+    fn action_space(&self) -> &DiscreteSpace<A> {
+        self.action_mask.action_space()
+    }
+}
+
+/// A legal action of `observation` on `action_mask`, chosen uniformly at random.
+///
+/// # Panics
+/// Panics if `observation` has no legal actions.
+fn random_legal_action<A: SpaceElement, S>(
+    action_mask: &ActionMask<S, A>,
+    observation: &S,
+    rng_facade: &impl RandomNumberGeneratorFacade,
+) -> A {
+    let legal_actions = action_mask.legal_actions(observation).count();
+    assert!(
+        legal_actions > 0,
+        "The observation must have a legal action."
+    );
+    action_mask
+        .legal_actions(observation)
+        .nth(rng_facade.random_range(0..legal_actions))
+        .expect("The chosen action must be one of the legal actions.")
+}
+
+/// A legal action of `observation` on `action_mask` with the highest of
+/// `action_values`, chosen uniformly at random among the ties.
+///
+/// # Panics
+/// Panics if `observation` has no legal actions.
+fn random_legal_argmax<A: SpaceElement, S>(
+    action_mask: &ActionMask<S, A>,
+    observation: &S,
+    action_values: &[f64],
+    rng_facade: &impl RandomNumberGeneratorFacade,
+) -> A {
+    let max = action_mask
+        .legal_indices(observation)
+        .map(|index| action_values[index])
+        .max_by(f64::total_cmp)
+        .expect("The observation must have a legal action.");
+    let ties = || {
+        action_mask
+            .legal_indices(observation)
+            .filter(move |index| action_values[*index].total_cmp(&max).is_eq())
+    };
+    let chosen = rng_facade.random_range(0..ties().count());
+    ties()
+        .nth(chosen)
+        .map(|index| action_mask.action_space().get(index))
+        .expect("The chosen tie must be one of the legal actions.")
 }
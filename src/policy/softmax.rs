@@ -0,0 +1,255 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{
+    action::{Action, DiscreteAction},
+    observation::{DiscreteObservation, Observation},
+    policy::{Policy, StochasticPolicy},
+    random_number_generator::RandomNumberGeneratorFacade,
+    schedule::{Schedule, ScheduleTiming},
+    space::{ActionMask, DiscreteSpace, SpaceElement},
+    value_table::{Dense, Storage, StorageTable, ValueStorage},
+};
+
+// Docs imports
+#[allow(unused_imports)]
+use crate::environment::DiscreteEnvironment;
+
+#[derive(Debug)]
+pub enum SoftmaxPolicyError {
+    TemperatureNotPositive,
+}
+
+impl Display for SoftmaxPolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let m = match self {
+            Self::TemperatureNotPositive => "Temperature must be positive.",
+        };
+        write!(f, "{m}")
+    }
+}
+
+impl Error for SoftmaxPolicyError {}
+
+/// Boltzmann exploration, takes each action with probability proportional to
+/// `exp(Q(s, a) / τ)`, where `τ` is the temperature.
+///
+/// High temperatures explore almost uniformly, while low temperatures are almost
+/// greedy. Unlike [`EpsilonGreedyPolicy`](super::epsilon_greedy::EpsilonGreedyPolicy),
+/// actions with close values are explored more often than clearly worse ones.
+///
+/// Illegal actions of its [`ActionMask`] have probability zero, the probabilities
+/// are normalized over the legal ones. The values of the actions are kept in the [`Storage`] `ST`.
+pub struct SoftmaxPolicy<
+    A: SpaceElement,
+    S: SpaceElement,
+    RNG: RandomNumberGeneratorFacade,
+    ST: Storage = Dense,
+> {
+    temperature: f64,
+    schedule: Schedule,
+    schedule_timing: ScheduleTiming,
+    /// Number of times the schedule was advanced
+    schedule_advances: usize,
+    rng_facade: RNG,
+    observation_space: DiscreteSpace<S>,
+    action_mask: ActionMask<S, A>,
+    action_values: StorageTable<ST, f64>,
+}
+
+impl<A: DiscreteAction, S: DiscreteObservation, RNG: RandomNumberGeneratorFacade, ST: Storage>
+    SoftmaxPolicy<A, S, RNG, ST>
+{
+    pub fn new(temperature: f64, rng_facade: RNG) -> Result<Self, SoftmaxPolicyError> {
+        Self::with_schedule(
+            Schedule::Constant(temperature),
+            ScheduleTiming::PerEpisode,
+            rng_facade,
+        )
+    }
+
+    /// Policy whose temperature follows `schedule`, advanced by the estimators as
+    /// given by `schedule_timing`.
+    pub fn with_schedule(
+        schedule: Schedule,
+        schedule_timing: ScheduleTiming,
+        rng_facade: RNG,
+    ) -> Result<Self, SoftmaxPolicyError> {
+        Self::with_spaces(
+            DiscreteSpace::observations(),
+            DiscreteSpace::actions(),
+            schedule,
+            schedule_timing,
+            rng_facade,
+        )
+    }
+}
+
+impl<A: SpaceElement, S: SpaceElement, RNG: RandomNumberGeneratorFacade, ST: Storage>
+    SoftmaxPolicy<A, S, RNG, ST>
+{
+    /// Same as [`SoftmaxPolicy::with_schedule`], but over the observations of
+    /// `observation_space` and the actions of `action_space`, usually the spaces of
+    /// the [`DiscreteEnvironment`].
+    pub fn with_spaces(
+        observation_space: DiscreteSpace<S>,
+        action_space: DiscreteSpace<A>,
+        schedule: Schedule,
+        schedule_timing: ScheduleTiming,
+        rng_facade: RNG,
+    ) -> Result<Self, SoftmaxPolicyError> {
+        if schedule.bounds().is_some_and(|(min, _)| min > 0.) {
+            Ok(Self {
+                temperature: schedule.value(0),
+                schedule,
+                schedule_timing,
+                schedule_advances: 0,
+                rng_facade,
+                action_values: StorageTable::<ST, f64>::with_default(
+                    observation_space.len(),
+                    action_space.len(),
+                    0.,
+                ),
+                observation_space,
+                action_mask: ActionMask::all(action_space),
+            })
+        } else {
+            Err(SoftmaxPolicyError::TemperatureNotPositive)
+        }
+    }
+
+    /// Restricts the policy to the legal actions of `action_mask`, over its action space.
+    #[must_use]
+    pub fn with_action_mask(mut self, action_mask: ActionMask<S, A>) -> Self {
+        self.action_mask.replace(action_mask);
+        self
+    }
+
+    /// Multiplies the temperature by `decay` at the end of every episode, without
+    /// going below `min_temperature`. Shorthand for a [`Schedule::Exponential`].
+    ///
+    /// # Panics
+    /// Panics if `decay` is not in `(0, 1]` or `min_temperature` is not positive.
+    #[must_use]
+    pub fn with_annealing(mut self, decay: f64, min_temperature: f64) -> Self {
+        assert!(decay > 0. && decay <= 1., "Decay must be between (0, 1].");
+        assert!(
+            min_temperature > 0.,
+            "Minimum temperature must be positive."
+        );
+        self.schedule = Schedule::Exponential {
+            start: self.temperature,
+            decay,
+            min: min_temperature,
+        };
+        self.schedule_timing = ScheduleTiming::PerEpisode;
+        self.schedule_advances = 0;
+        self
+    }
+
+    fn advance_schedule(&mut self, timing: ScheduleTiming) {
+        if self.schedule_timing == timing {
+            self.schedule_advances += 1;
+            self.temperature = self.schedule.value(self.schedule_advances);
+        }
+    }
+
+    /// Current temperature of the policy.
+    #[must_use]
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    fn observation_values(&self, observation: &S) -> &[f64] {
+        self.action_values
+            .row(self.observation_space.index_of(observation))
+    }
+
+    /// Logarithm of the normalization of the probabilities, `ln Σ exp(Q(s, a) / τ)`
+    /// over the legal actions of `observation`, shifted by the maximum value so the
+    /// exponentials don't overflow.
+    fn log_sum_exp(&self, observation: &S, values: &[f64]) -> f64 {
+        let legal_values = || {
+            self.action_mask
+                .legal_indices(observation)
+                .map(|index| values[index] / self.temperature)
+        };
+        let max = legal_values()
+            .max_by(|lhs, rhs| lhs.total_cmp(rhs))
+            .expect("There must be a legal action with maximum value.");
+        max + legal_values()
+            .map(|value| (value - max).exp())
+            .fold(0., |sum, exponential| sum + exponential)
+            .ln()
+    }
+}
+
+impl<
+        A: Action + SpaceElement,
+        S: Observation + SpaceElement,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > Policy for SoftmaxPolicy<A, S, RNG, ST>
+{
+    type Observation = S;
+    type Action = A;
+
+    fn act(&self, observation: &Self::Observation) -> Self::Action {
+        self.action_mask.action_space().get(
+            self.rng_facade
+                .random_weighted(&self.action_distribution(observation)),
+        )
+    }
+
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.action_values
+            .row_mut(self.observation_space.index_of(observation))
+            .copy_from_slice(action_values);
+    }
+
+    fn on_episode_end(&mut self) {
+        self.advance_schedule(ScheduleTiming::PerEpisode);
+    }
+
+    fn on_step_end(&mut self) {
+        self.advance_schedule(ScheduleTiming::PerStep);
+    }
+}
+
+impl<
+        A: Action + SpaceElement,
+        S: Observation + SpaceElement,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > StochasticPolicy<A, S> for SoftmaxPolicy<A, S, RNG, ST>
+{
+    fn action_probability(&self, action: &A, observation: &S) -> f64 {
+        if !self.action_mask.is_legal(observation, action) {
+            return 0.;
+        }
+        let values = self.observation_values(observation);
+        (values[self.action_mask.action_space().index_of(action)] / self.temperature
+            - self.log_sum_exp(observation, values))
+        .exp()
+    }
+
+    fn action_space(&self) -> &DiscreteSpace<A> {
+        self.action_mask.action_space()
+    }
+
+    fn action_distribution(&self, observation: &S) -> Vec<f64> {
+        let values = self.observation_values(observation);
+        let log_sum_exp = self.log_sum_exp(observation, values);
+        self.action_mask
+            .action_space()
+            .iter()
+            .zip(values)
+            .map(|(action, value)| {
+                if self.action_mask.is_legal(observation, &action) {
+                    (value / self.temperature - log_sum_exp).exp()
+                } else {
+                    0.
+                }
+            })
+            .collect()
+    }
+}
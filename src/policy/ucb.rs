@@ -0,0 +1,163 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{
+    action::{Action, DiscreteAction},
+    observation::{DiscreteObservation, Observation},
+    policy::{Policy, StochasticPolicy},
+    space::{ActionMask, DiscreteSpace, SpaceElement},
+    value_table::{Dense, Storage, StorageTable, ValueStorage},
+};
+
+// Docs imports
+#[allow(unused_imports)]
+use crate::environment::DiscreteEnvironment;
+
+#[derive(Debug)]
+pub enum UcbPolicyError {
+    NegativeExploration,
+}
+
+impl Display for UcbPolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let m = match self {
+            Self::NegativeExploration => "Exploration must not be negative.",
+        };
+        write!(f, "{m}")
+    }
+}
+
+impl Error for UcbPolicyError {}
+
+/// Upper Confidence Bound (UCB1), takes the action that maximizes
+/// `Q(s, a) + c sqrt(ln N(s) / n(s, a))`, where `n(s, a)` is the number of visits
+/// of the pair and `N(s)` the number of visits of all the actions of `s`.
+///
+/// Actions that were never visited are taken first. Only the legal actions of its
+/// [`ActionMask`] are ever taken. The visits are only known
+/// through [`Policy::policy_improvement_with_visits`], so agents must forward it.
+/// The values and visits are kept in the [`Storage`] `ST`.
+pub struct UcbPolicy<A: SpaceElement, S: SpaceElement, ST: Storage = Dense> {
+    exploration: f64,
+    observation_space: DiscreteSpace<S>,
+    action_mask: ActionMask<S, A>,
+    action_values: StorageTable<ST, f64>,
+    visit_counts: StorageTable<ST, usize>,
+}
+
+impl<A: DiscreteAction, S: DiscreteObservation, ST: Storage> UcbPolicy<A, S, ST> {
+    /// # Arguments
+    /// `exploration`: The constant `c`, how much the uncertainty of the values is explored
+    pub fn new(exploration: f64) -> Result<Self, UcbPolicyError> {
+        Self::with_spaces(
+            DiscreteSpace::observations(),
+            DiscreteSpace::actions(),
+            exploration,
+        )
+    }
+}
+
+impl<A: SpaceElement, S: SpaceElement, ST: Storage> UcbPolicy<A, S, ST> {
+    /// Same as [`UcbPolicy::new`], but over the observations of `observation_space`
+    /// and the actions of `action_space`, usually the spaces of the
+    /// [`DiscreteEnvironment`].
+    pub fn with_spaces(
+        observation_space: DiscreteSpace<S>,
+        action_space: DiscreteSpace<A>,
+        exploration: f64,
+    ) -> Result<Self, UcbPolicyError> {
+        if exploration >= 0. {
+            Ok(Self {
+                exploration,
+                action_values: StorageTable::<ST, f64>::with_default(
+                    observation_space.len(),
+                    action_space.len(),
+                    0.,
+                ),
+                visit_counts: StorageTable::<ST, usize>::with_default(
+                    observation_space.len(),
+                    action_space.len(),
+                    0,
+                ),
+                observation_space,
+                action_mask: ActionMask::all(action_space),
+            })
+        } else {
+            Err(UcbPolicyError::NegativeExploration)
+        }
+    }
+
+    /// Restricts the policy to the legal actions of `action_mask`, over its action space.
+    #[must_use]
+    pub fn with_action_mask(mut self, action_mask: ActionMask<S, A>) -> Self {
+        self.action_mask.replace(action_mask);
+        self
+    }
+}
+
+impl<A: Action + SpaceElement, S: Observation + SpaceElement, ST: Storage> Policy
+    for UcbPolicy<A, S, ST>
+{
+    type Observation = S;
+    type Action = A;
+
+    fn act(&self, observation: &Self::Observation) -> Self::Action {
+        let observation_index = self.observation_space.index_of(observation);
+        let values = self.action_values.row(observation_index);
+        let visits = self.visit_counts.row(observation_index);
+
+        let legal_indices = || self.action_mask.legal_indices(observation);
+
+        let index = match legal_indices().find(|index| visits[*index] == 0) {
+            Some(untried) => untried,
+            None => {
+                let total_visits_ln =
+                    (legal_indices().map(|index| visits[index]).sum::<usize>() as f64).ln();
+                legal_indices()
+                    .map(|index| {
+                        let bound = values[index]
+                            + self.exploration * (total_visits_ln / visits[index] as f64).sqrt();
+                        (index, bound)
+                    })
+                    .max_by(|(_, lhs), (_, rhs)| lhs.total_cmp(rhs))
+                    .map(|(index, _)| index)
+                    .expect("There must be a legal action with maximum upper confidence bound.")
+            }
+        };
+        self.action_mask.action_space().get(index)
+    }
+
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.action_values
+            .row_mut(self.observation_space.index_of(observation))
+            .copy_from_slice(action_values);
+    }
+
+    fn policy_improvement_with_visits(
+        &mut self,
+        observation: &Self::Observation,
+        action_values: &[f64],
+        visits: &[usize],
+    ) {
+        self.policy_improvemnt(observation, action_values);
+        self.visit_counts
+            .row_mut(self.observation_space.index_of(observation))
+            .copy_from_slice(visits);
+    }
+}
+
+/// UCB is deterministic, the action it takes has probability one.
+impl<A: Action + SpaceElement, S: Observation + SpaceElement, ST: Storage> StochasticPolicy<A, S>
+    for UcbPolicy<A, S, ST>
+{
+    fn action_probability(&self, action: &A, observation: &S) -> f64 {
+        if self.act(observation).eq(action) {
+            1.
+        } else {
+            0.
+        }
+    }
+
+    fn action_space(&self) -> &DiscreteSpace<A> {
+        self.action_mask.action_space()
+    }
+}
@@ -1,5 +1,90 @@
+use std::{error::Error, fmt::Display, ops::Range};
+
+mod xoshiro;
+
+pub use self::xoshiro::Xoshiro256PlusPlus;
+
+#[derive(Debug)]
+pub enum RandomNumberGeneratorError {
+    InvalidState,
+}
+
+impl Display for RandomNumberGeneratorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let m = match self {
+            Self::InvalidState => "The state of the generator must not be all zeros.",
+        };
+        write!(f, "{m}")
+    }
+}
+
+impl Error for RandomNumberGeneratorError {}
+
 /// A Facade for an object that can generate random numbers.
 pub trait RandomNumberGeneratorFacade {
     /// Returns a number between `0.0f64` and `1.0f64`
     fn random(&self) -> f64;
+
+    /// Returns an integer uniformly distributed over `range`.
+    ///
+    /// # Panics
+    /// Panics if `range` is empty.
+    fn random_range(&self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "Range must not be empty.");
+        let len = range.end - range.start;
+        range.start + ((self.random() * len as f64) as usize).min(len - 1)
+    }
+
+    /// Returns a sample of the normal distribution with mean `mean` and standard
+    /// deviation `standard_deviation`, using the Box-Muller transform.
+    fn random_gaussian(&self, mean: f64, standard_deviation: f64) -> f64 {
+        let u1 = (1. - self.random()).max(f64::MIN_POSITIVE);
+        let u2 = self.random();
+        mean + standard_deviation * (-2. * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+    }
+
+    /// Returns an index of `weights` with probability proportional to its weight.
+    ///
+    /// # Panics
+    /// Panics if `weights` doesn't have any positive weight.
+    fn random_weighted(&self, weights: &[f64]) -> usize {
+        let total: f64 = weights.iter().filter(|weight| **weight > 0.).sum();
+        assert!(total > 0., "There must be at least one positive weight.");
+
+        let mut target = self.random() * total;
+        let mut last_positive = 0;
+        for (index, weight) in weights.iter().enumerate() {
+            if *weight > 0. {
+                if target < *weight {
+                    return index;
+                }
+                target -= weight;
+                last_positive = index;
+            }
+        }
+        // Rounding errors can leave `target` slightly above zero
+        last_positive
+    }
+
+    /// Returns an index of the maximum of `values`, chosen uniformly at random
+    /// among the ties.
+    ///
+    /// # Panics
+    /// Panics if `values` is empty.
+    fn random_argmax(&self, values: &[f64]) -> usize {
+        let max = values
+            .iter()
+            .copied()
+            .max_by(f64::total_cmp)
+            .expect("There must be at least one value.");
+        let ties = values.iter().filter(|value| value.total_cmp(&max).is_eq());
+        let chosen = self.random_range(0..ties.count());
+        values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.total_cmp(&max).is_eq())
+            .nth(chosen)
+            .map(|(index, _)| index)
+            .expect("The chosen tie must be one of the values.")
+    }
 }
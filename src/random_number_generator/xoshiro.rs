@@ -0,0 +1,168 @@
+use std::cell::Cell;
+
+use crate::random_number_generator::{RandomNumberGeneratorError, RandomNumberGeneratorFacade};
+
+/// Seedable pseudo random number generator using the `xoshiro256++` algorithm.
+///
+/// The generator is not cryptographically secure, but is fast and has a period of `2^256 - 1`,
+/// which makes it suitable for reproducible experiments.
+#[derive(Debug, Clone)]
+pub struct Xoshiro256PlusPlus {
+    state: Cell<[u64; 4]>,
+}
+
+impl Xoshiro256PlusPlus {
+    /// Creates a generator whose state is derived from `seed` using `SplitMix64`.
+    #[must_use]
+    pub fn from_seed(seed: u64) -> Self {
+        let mut split_mix = seed;
+        let mut next = || {
+            split_mix = split_mix.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = split_mix;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        Self {
+            state: Cell::new([next(), next(), next(), next()]),
+        }
+    }
+
+    /// Restores a generator from a state returned by [`Xoshiro256PlusPlus::state`].
+    ///
+    /// # Errors
+    /// Returns [`RandomNumberGeneratorError::InvalidState`] if all words of the state are zero.
+    pub fn from_state(state: [u64; 4]) -> Result<Self, RandomNumberGeneratorError> {
+        if state.iter().all(|word| *word == 0) {
+            Err(RandomNumberGeneratorError::InvalidState)
+        } else {
+            Ok(Self {
+                state: Cell::new(state),
+            })
+        }
+    }
+
+    /// Current state of the generator, can be used to resume the sequence with
+    /// [`Xoshiro256PlusPlus::from_state`].
+    #[must_use]
+    pub fn state(&self) -> [u64; 4] {
+        self.state.get()
+    }
+
+    /// Returns a generator that continues the current sequence, while this generator
+    /// jumps `2^128` numbers ahead.
+    ///
+    /// Streams created by successive forks do not overlap for `2^128` numbers.
+    #[must_use]
+    pub fn fork(&self) -> Self {
+        let forked = Self {
+            state: Cell::new(self.state.get()),
+        };
+        self.jump();
+        forked
+    }
+
+    /// Returns the next `u64` of the sequence.
+    pub fn next_u64(&self) -> u64 {
+        let mut s = self.state.get();
+        let result = s[0].wrapping_add(s[3]).rotate_left(23).wrapping_add(s[0]);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        self.state.set(s);
+        result
+    }
+
+    /// Advances the generator `2^128` numbers.
+    fn jump(&self) {
+        const JUMP: [u64; 4] = [
+            0x180e_c6d3_3cfd_0aba,
+            0xd5a6_1266_f0c9_392c,
+            0xa958_2618_e03f_c9aa,
+            0x39ab_dc45_29b1_661c,
+        ];
+
+        let mut jumped = [0u64; 4];
+        for word in JUMP {
+            for bit in 0..64 {
+                if word & (1u64 << bit) != 0 {
+                    let s = self.state.get();
+                    jumped
+                        .iter_mut()
+                        .zip(s)
+                        .for_each(|(jumped, s)| *jumped ^= s);
+                }
+                self.next_u64();
+            }
+        }
+        self.state.set(jumped);
+    }
+}
+
+impl RandomNumberGeneratorFacade for Xoshiro256PlusPlus {
+    fn random(&self) -> f64 {
+        // Uses the 53 most significant bits, the precision of a `f64` mantissa
+        (self.next_u64() >> 11) as f64 * (1. / (1u64 << 53) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// First numbers of the reference implementation from the state `[1, 2, 3, 4]`.
+    #[test]
+    fn known_answers() {
+        let generator = Xoshiro256PlusPlus::from_state([1, 2, 3, 4]).unwrap();
+        let expected = [
+            41_943_041,
+            58_720_359,
+            3_588_806_011_781_223,
+            3_591_011_842_654_386,
+            9_228_616_714_210_784_205,
+            9_973_669_472_204_895_162,
+            14_011_001_112_246_962_877,
+            12_406_186_145_184_390_807,
+            15_849_039_046_786_891_736,
+            10_450_023_813_501_588_000,
+        ];
+        for number in expected {
+            assert_eq!(generator.next_u64(), number);
+        }
+    }
+
+    #[test]
+    fn fork_continues_the_sequence_and_jumps_the_parent() {
+        let parent = Xoshiro256PlusPlus::from_seed(7);
+        let sequence = Xoshiro256PlusPlus::from_state(parent.state()).unwrap();
+
+        let forked = parent.fork();
+        for _ in 0..10 {
+            assert_eq!(forked.next_u64(), sequence.next_u64());
+        }
+        assert_ne!(parent.state(), forked.state());
+    }
+
+    #[test]
+    fn jumps_are_deterministic() {
+        let generator = Xoshiro256PlusPlus::from_seed(7);
+        let copy = generator.clone();
+        generator.jump();
+        copy.jump();
+        assert_eq!(generator.state(), copy.state());
+
+        // Forking jumps the parent the same as jumping directly
+        let first = Xoshiro256PlusPlus::from_seed(7);
+        let second = Xoshiro256PlusPlus::from_seed(7);
+        let _ = first.fork();
+        let _ = second.fork();
+        assert_eq!(first.state(), second.state());
+        assert_eq!(first.state(), generator.state());
+    }
+}
@@ -0,0 +1,111 @@
+use std::marker::PhantomData;
+
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, Environment},
+    reinforcement_learning::{
+        average_reward::{AverageReward, AverageRewardConfiguration},
+        PolicyEstimator, StoppingCriterion, TrainingObserver, TrainingReport,
+    },
+    schedule::StepSize,
+    space::{ActionMask, SpaceElement},
+    value_table::{Dense, QTable, Storage},
+};
+
+/// Differential SARSA, updates the value of `(s, a)` with the error
+/// `δ = r - R̄ + Q(s', a') - Q(s, a)`, where `R̄` is the estimate of the average
+/// reward, updated with `R̄ = R̄ + β δ`.
+pub struct DifferentialSARSA<E: Environment, ST: Storage = Dense> {
+    stopping_criterion: StoppingCriterion,
+    step_size: StepSize,
+    average_reward_learning_rate: f64,
+    phantom_env: PhantomData<(E, ST)>,
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: Environment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > DifferentialSARSA<E, ST>
+{
+    /// # Arguments
+    /// `step_limit`: Number of steps before the search stops</br>
+    /// `alpha`: Learning rate of the action values</br>
+    /// `beta`: Learning rate of the average reward
+    pub fn new(step_limit: usize, alpha: f64, beta: f64) -> Self {
+        Self {
+            stopping_criterion: StoppingCriterion::MaxSteps(step_limit),
+            step_size: StepSize::Constant(alpha),
+            average_reward_learning_rate: beta,
+            phantom_env: PhantomData,
+        }
+    }
+
+    /// Replaces the [`StepSize`] of the updates of the action values, which is
+    /// `alpha` by default.
+    #[must_use]
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = step_size;
+        self
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: Environment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > PolicyEstimator for DifferentialSARSA<E, ST>
+{
+    type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn policy_search_with_observer(
+        self,
+        environment: &mut Self::Environment,
+        agent: &mut <Self::Environment as Environment>::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
+        self.average_reward_policy_search(
+            environment,
+            agent,
+            observer,
+            &AverageRewardConfiguration {
+                stopping_criterion: self.stopping_criterion.clone(),
+                step_size: self.step_size,
+                average_reward_learning_rate: self.average_reward_learning_rate,
+            },
+        )
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: Environment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > AverageReward<AC, S, AG, E> for DifferentialSARSA<E, ST>
+{
+    fn differential_error(
+        &self,
+        action_value: &QTable<S, AC, ST>,
+        _action_mask: &ActionMask<S, AC>,
+        average_reward: &mut f64,
+        (s, a, reward, next_s, next_a): (&S, &AC, f64, &S, &AC),
+        average_reward_configuration: &AverageRewardConfiguration,
+    ) -> f64 {
+        let differential_error =
+            reward - *average_reward + action_value[(next_s, next_a)] - action_value[(s, a)];
+        *average_reward +=
+            average_reward_configuration.average_reward_learning_rate * differential_error;
+        differential_error
+    }
+}
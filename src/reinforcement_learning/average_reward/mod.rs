@@ -0,0 +1,142 @@
+mod differential_sarsa;
+mod r_learning;
+
+// Re-exports
+pub use self::{differential_sarsa::DifferentialSARSA, r_learning::RLearning};
+
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, Environment},
+    reinforcement_learning::{
+        stopping_criterion::TrainingProgress, DiscretePolicyEstimator, PolicyEstimator,
+        StoppingCriterion, TrainingObserver, TrainingReport,
+    },
+    schedule::{StepSize, StepSizes},
+    space::{ActionMask, SpaceElement},
+    value_table::{ObservationActionTable, QTable},
+};
+
+struct AverageRewardConfiguration {
+    pub stopping_criterion: StoppingCriterion,
+    pub step_size: StepSize,
+    pub average_reward_learning_rate: f64,
+}
+
+/// Searches on continuing tasks, where the [`Environment`] never ends and the
+/// values are relative to the average reward per step.
+///
+/// As there are no episodes, the windows of the [`StoppingCriterion`] are measured
+/// in steps and the [Agent] is never told that an episode ended, so only the
+/// [`ScheduleTiming::PerStep`](crate::schedule::ScheduleTiming::PerStep) schedules of its policy advance.
+trait AverageReward<
+    AC: SpaceElement,
+    S: SpaceElement,
+    AG: Agent<Action = AC, Observation = S>,
+    E: Environment<Agent = AG> + DiscreteEnvironment,
+>: PolicyEstimator<Environment = E>
+{
+    /// Calculates the differential temporal difference error of the `transition`
+    /// `(s, a, r, s', a')` and updates the `average_reward` estimate. Estimators that
+    /// maximize over actions only consider the legal ones of `action_mask`.
+    fn differential_error(
+        &self,
+        action_value: &QTable<S, AC, Self::Storage>,
+        action_mask: &ActionMask<S, AC>,
+        average_reward: &mut f64,
+        transition: (&S, &AC, f64, &S, &AC),
+        average_reward_configuration: &AverageRewardConfiguration,
+    ) -> f64;
+
+    fn average_reward_policy_search(
+        &self,
+        environment: &mut Self::Environment,
+        agent: &mut <Self::Environment as Environment>::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+        average_reward_configuration: &AverageRewardConfiguration,
+    ) -> TrainingReport<S, AC, Self::Storage> {
+        assert!(
+            !average_reward_configuration
+                .stopping_criterion
+                .counts_episodes(),
+            "Continuing tasks have no episodes to stop after."
+        );
+        let observation_space = environment.observation_space();
+        let action_space = environment.action_space();
+        let action_mask = environment.action_mask();
+        let mut step_sizes = StepSizes::<Self::Storage>::new(
+            average_reward_configuration.step_size,
+            observation_space.len(),
+            action_space.len(),
+        );
+        let mut action_value = QTable::<S, AC, Self::Storage>::with_spaces(
+            observation_space.clone(),
+            action_space.clone(),
+            0.,
+        );
+        let mut visit_count = ObservationActionTable::<S, AC, usize, Self::Storage>::with_spaces(
+            observation_space,
+            action_space,
+            0,
+        );
+        let mut average_reward = 0.;
+
+        let mut observation = environment
+            .get_observation(agent)
+            .expect("A continuing Environment must always have an Observation.");
+        let mut action = agent.act(&observation);
+
+        let mut progress = TrainingProgress::new(&average_reward_configuration.stopping_criterion);
+        let stopped_by = loop {
+            if let Some(stopped_by) = progress.stopping_criterion() {
+                break stopped_by;
+            }
+            let reward = environment.receive_action(agent, &action);
+            observer.on_step(&observation, &action, reward);
+            agent.on_step_end();
+
+            let next_observation = environment
+                .get_observation(agent)
+                .expect("A continuing Environment must always have an Observation.");
+            let next_action = agent.act(&next_observation);
+
+            let differential_error = self.differential_error(
+                &action_value,
+                &action_mask,
+                &mut average_reward,
+                (
+                    &observation,
+                    &action,
+                    reward,
+                    &next_observation,
+                    &next_action,
+                ),
+                average_reward_configuration,
+            );
+
+            let index = action_value.tabular_index(&observation, &action);
+            visit_count[index] += 1;
+            let learning_rate = step_sizes.next(index, visit_count[index]);
+            let old_value = action_value[index];
+
+            // Update state-action value
+            action_value[index] += learning_rate * differential_error;
+            observer.on_value_update(&observation, &action, old_value, action_value[index]);
+            // Propagate change to policy
+            Self::improve_policy(agent, &observation, &action_value, &visit_count);
+
+            progress.end_step(reward, (old_value - action_value[index]).powi(2));
+            observation = next_observation;
+            action = next_action;
+        };
+
+        TrainingReport {
+            action_values: action_value,
+            visit_counts: visit_count,
+            episodes: 0,
+            steps: progress.steps(),
+            converged: stopped_by.is_convergence(),
+            stopped_by,
+            average_reward: Some(average_reward),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::marker::PhantomData;
+
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, Environment},
+    reinforcement_learning::{
+        average_reward::{AverageReward, AverageRewardConfiguration},
+        PolicyEstimator, StoppingCriterion, TrainingObserver, TrainingReport,
+    },
+    schedule::StepSize,
+    space::{ActionMask, SpaceElement},
+    value_table::{Dense, QTable, Storage},
+};
+
+/// R-learning, the off-policy counterpart of [`DifferentialSARSA`](super::DifferentialSARSA).
+///
+/// Updates the value of `(s, a)` with the error `δ = r - ρ + max Q(s', ·) - Q(s, a)`.
+/// The average reward `ρ` is only updated on greedy actions, with
+/// `ρ = ρ + β (r - ρ + max Q(s', ·) - max Q(s, ·))`.
+pub struct RLearning<E: Environment, ST: Storage = Dense> {
+    stopping_criterion: StoppingCriterion,
+    step_size: StepSize,
+    average_reward_learning_rate: f64,
+    phantom_env: PhantomData<(E, ST)>,
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: Environment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > RLearning<E, ST>
+{
+    /// # Arguments
+    /// `step_limit`: Number of steps before the search stops</br>
+    /// `alpha`: Learning rate of the action values</br>
+    /// `beta`: Learning rate of the average reward
+    pub fn new(step_limit: usize, alpha: f64, beta: f64) -> Self {
+        Self {
+            stopping_criterion: StoppingCriterion::MaxSteps(step_limit),
+            step_size: StepSize::Constant(alpha),
+            average_reward_learning_rate: beta,
+            phantom_env: PhantomData,
+        }
+    }
+
+    /// Replaces the [`StepSize`] of the updates of the action values, which is
+    /// `alpha` by default.
+    #[must_use]
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = step_size;
+        self
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: Environment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > PolicyEstimator for RLearning<E, ST>
+{
+    type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn policy_search_with_observer(
+        self,
+        environment: &mut Self::Environment,
+        agent: &mut <Self::Environment as Environment>::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
+        self.average_reward_policy_search(
+            environment,
+            agent,
+            observer,
+            &AverageRewardConfiguration {
+                stopping_criterion: self.stopping_criterion.clone(),
+                step_size: self.step_size,
+                average_reward_learning_rate: self.average_reward_learning_rate,
+            },
+        )
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: Environment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > AverageReward<AC, S, AG, E> for RLearning<E, ST>
+{
+    fn differential_error(
+        &self,
+        action_value: &QTable<S, AC, ST>,
+        action_mask: &ActionMask<S, AC>,
+        average_reward: &mut f64,
+        (s, a, reward, next_s, _next_a): (&S, &AC, f64, &S, &AC),
+        average_reward_configuration: &AverageRewardConfiguration,
+    ) -> f64 {
+        let max_value = action_value.legal_max_value(s, action_mask);
+        let next_max_value = action_value.legal_max_value(next_s, action_mask);
+        let value = action_value[(s, a)];
+
+        let differential_error = reward - *average_reward + next_max_value - value;
+        // The average reward is only estimated from the greedy actions
+        if value >= max_value {
+            *average_reward += average_reward_configuration.average_reward_learning_rate
+                * (reward - *average_reward + next_max_value - max_value);
+        }
+        differential_error
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use crate::{
+    action::DiscreteAction,
+    observation::DiscreteObservation,
+    value_table::{QTable, VTable},
+};
+
+/// Result of a dynamic programming solver.
+pub struct DynamicProgrammingReport<S, AC> {
+    /// Action value of each observation-action pair.
+    pub action_values: QTable<S, AC>,
+    /// State value of each observation.
+    pub state_values: VTable<S>,
+    /// Number of sweeps over all observations.
+    pub sweeps: usize,
+    /// Whether the values changed less than the tolerance on the last sweep, and for
+    /// [`PolicyIteration`](super::PolicyIteration) whether the policy became stable.
+    pub converged: bool,
+}
+
+/// Writes the state values, the action values and the number of sweeps.
+impl<S: DiscreteObservation, AC: DiscreteAction> Display for DynamicProgrammingReport<S, AC> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "State Value Function")?;
+        write!(f, "{}", self.state_values)?;
+        writeln!(f, "Action Value Function")?;
+        write!(f, "{}", self.action_values)?;
+        writeln!(f, "Swept {} times.", self.sweeps)?;
+        if self.converged {
+            writeln!(f, "Converged.")
+        } else {
+            writeln!(f, "Did not converge.")
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Solvers that compute value functions from a complete [`TabularModel`] of the
+//! environment, instead of learning them from sampled experience.
+
+use crate::{
+    action::DiscreteAction, model::TabularModel, observation::DiscreteObservation,
+    value_table::QTable,
+};
+
+mod dynamic_programming_report;
+mod policy_evaluation;
+mod policy_iteration;
+mod value_iteration;
+
+pub use self::{
+    dynamic_programming_report::DynamicProgrammingReport, policy_evaluation::PolicyEvaluation,
+    policy_iteration::PolicyIteration, value_iteration::ValueIteration,
+};
+
+/// How the state values are updated during a sweep over all observations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sweep {
+    /// All values of a sweep are computed from the values of the previous sweep.
+    Synchronous,
+    /// Values are updated in place, in the order of [`DiscreteObservation::OBSERVATIONS`],
+    /// so later updates of a sweep already use the new values. Usually converges faster.
+    InPlace,
+}
+
+/// Expected return of taking `action` on `observation` and then following the
+/// policy evaluated by `state_values`,
+/// `Σ p(s', r | s, a) [r + γ V(s')]`.
+fn one_step_lookahead<S: DiscreteObservation, AC: DiscreteAction>(
+    model: &impl TabularModel<S, AC>,
+    observation: &S,
+    action: &AC,
+    state_values: &[f64],
+    discount_factor: f64,
+) -> f64 {
+    model
+        .transitions(observation, action)
+        .map(|(probability, next_observation, reward, terminal)| {
+            let next_value = if terminal {
+                0.
+            } else {
+                state_values[next_observation.checked_index()]
+            };
+            probability * (reward + discount_factor * next_value)
+        })
+        // Starts from `0.`, since summing no transitions would give `-0.`
+        .fold(0., |expected_return, value| expected_return + value)
+}
+
+/// Updates the value of every observation with `backup`.
+///
+/// # Return
+/// The largest change to a value
+fn sweep_state_values<S: DiscreteObservation>(
+    state_values: &mut Vec<f64>,
+    sweep: Sweep,
+    backup: impl Fn(&S, &[f64]) -> f64,
+) -> f64 {
+    match sweep {
+        Sweep::Synchronous => {
+            let new_state_values: Vec<f64> = S::OBSERVATIONS
+                .iter()
+                .map(|observation| backup(observation, state_values))
+                .collect();
+            let delta = new_state_values
+                .iter()
+                .zip(state_values.iter())
+                .map(|(new_value, old_value)| (new_value - old_value).abs())
+                .fold(0., f64::max);
+            *state_values = new_state_values;
+            delta
+        }
+        Sweep::InPlace => {
+            S::OBSERVATIONS
+                .iter()
+                .enumerate()
+                .fold(0., |delta, (index, observation)| {
+                    let new_value = backup(observation, state_values);
+                    let change = (new_value - state_values[index]).abs();
+                    state_values[index] = new_value;
+                    delta.max(change)
+                })
+        }
+    }
+}
+
+/// Action values of all observation-action pairs given the `state_values`.
+fn action_values<S: DiscreteObservation, AC: DiscreteAction>(
+    model: &impl TabularModel<S, AC>,
+    state_values: &[f64],
+    discount_factor: f64,
+) -> QTable<S, AC> {
+    QTable::from_values(
+        S::OBSERVATIONS
+            .iter()
+            .flat_map(|observation| {
+                AC::ACTIONS.iter().map(move |action| {
+                    one_step_lookahead(model, observation, action, state_values, discount_factor)
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Sweeps `state_values` with the Bellman expectation equation of the policy given
+/// by `action_probability` until the values change less than `tolerance`.
+///
+/// # Return
+/// Number of sweeps done and whether the values converged
+fn evaluate_policy<S: DiscreteObservation, AC: DiscreteAction>(
+    model: &impl TabularModel<S, AC>,
+    state_values: &mut Vec<f64>,
+    action_probability: impl Fn(&AC, &S) -> f64,
+    (discount_factor, tolerance, max_sweeps, sweep): (f64, f64, usize, Sweep),
+) -> (usize, bool) {
+    for sweeps in 1..=max_sweeps {
+        let delta = sweep_state_values(state_values, sweep, |observation: &S, state_values| {
+            AC::ACTIONS
+                .iter()
+                .map(|action| {
+                    let probability = action_probability(action, observation);
+                    if probability > 0. {
+                        probability
+                            * one_step_lookahead(
+                                model,
+                                observation,
+                                action,
+                                state_values,
+                                discount_factor,
+                            )
+                    } else {
+                        0.
+                    }
+                })
+                .sum()
+        });
+        if delta < tolerance {
+            return (sweeps, true);
+        }
+    }
+    (max_sweeps, false)
+}
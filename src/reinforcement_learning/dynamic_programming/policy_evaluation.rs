@@ -0,0 +1,67 @@
+use crate::{
+    action::DiscreteAction,
+    agent::DiscreteAgent,
+    model::TabularModel,
+    observation::DiscreteObservation,
+    reinforcement_learning::dynamic_programming::{
+        action_values, evaluate_policy, DynamicProgrammingReport, Sweep,
+    },
+    value_table::VTable,
+};
+
+/// Iterative policy evaluation, computes the values of the policy of an agent.
+pub struct PolicyEvaluation {
+    discount_factor: f64,
+    tolerance: f64,
+    max_sweeps: usize,
+    sweep: Sweep,
+}
+
+impl PolicyEvaluation {
+    /// # Arguments
+    /// `tolerance`: Evaluation stops when no value changes more than `tolerance` on a sweep</br>
+    /// `max_sweeps`: Evaluation stops after `max_sweeps` even if values didn't converge
+    #[must_use]
+    pub fn new(discount_factor: f64, tolerance: f64, max_sweeps: usize) -> Self {
+        Self {
+            discount_factor,
+            tolerance,
+            max_sweeps,
+            sweep: Sweep::Synchronous,
+        }
+    }
+
+    /// Replaces the [`Sweep`] used, [`Sweep::Synchronous`] by default.
+    #[must_use]
+    pub fn with_sweep(mut self, sweep: Sweep) -> Self {
+        self.sweep = sweep;
+        self
+    }
+
+    /// Values of the policy of `agent`, given by [`DiscreteAgent::action_probability`].
+    pub fn evaluate<S: DiscreteObservation, AC: DiscreteAction>(
+        &self,
+        model: &impl TabularModel<S, AC>,
+        agent: &impl DiscreteAgent<AC, S>,
+    ) -> DynamicProgrammingReport<S, AC> {
+        let mut state_values = vec![0.; S::COUNT];
+        let (sweeps, converged) = evaluate_policy(
+            model,
+            &mut state_values,
+            |action, observation| agent.action_probability(action, observation),
+            (
+                self.discount_factor,
+                self.tolerance,
+                self.max_sweeps,
+                self.sweep,
+            ),
+        );
+
+        DynamicProgrammingReport {
+            action_values: action_values(model, &state_values, self.discount_factor),
+            state_values: VTable::from_values(state_values),
+            sweeps,
+            converged,
+        }
+    }
+}
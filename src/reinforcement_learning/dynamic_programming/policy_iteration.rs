@@ -0,0 +1,114 @@
+use crate::{
+    action::DiscreteAction,
+    model::TabularModel,
+    observation::DiscreteObservation,
+    reinforcement_learning::dynamic_programming::{
+        action_values, evaluate_policy, DynamicProgrammingReport, Sweep,
+    },
+    value_table::VTable,
+};
+
+/// Policy iteration, alternates evaluating a deterministic policy and making it greedy
+/// with respect to its values, until the policy is stable.
+pub struct PolicyIteration {
+    discount_factor: f64,
+    tolerance: f64,
+    max_sweeps: usize,
+    max_improvements: usize,
+    sweep: Sweep,
+}
+
+impl PolicyIteration {
+    /// # Arguments
+    /// `tolerance`: Each evaluation stops when no value changes more than `tolerance` on a sweep</br>
+    /// `max_sweeps`: Each evaluation stops after `max_sweeps` even if values didn't converge
+    #[must_use]
+    pub fn new(discount_factor: f64, tolerance: f64, max_sweeps: usize) -> Self {
+        Self {
+            discount_factor,
+            tolerance,
+            max_sweeps,
+            max_improvements: 1000,
+            sweep: Sweep::Synchronous,
+        }
+    }
+
+    /// Replaces the maximum number of policy improvements, 1000 by default. Evaluations
+    /// cut off by `max_sweeps` can keep changing the policy, the solver stops without
+    /// converging once it was improved `max_improvements` times.
+    #[must_use]
+    pub fn with_max_improvements(mut self, max_improvements: usize) -> Self {
+        self.max_improvements = max_improvements;
+        self
+    }
+
+    /// Replaces the [`Sweep`] used, [`Sweep::Synchronous`] by default.
+    #[must_use]
+    pub fn with_sweep(mut self, sweep: Sweep) -> Self {
+        self.sweep = sweep;
+        self
+    }
+
+    /// Optimal values of `model`, the optimal policy is greedy with respect to
+    /// [`DynamicProgrammingReport::action_values`].
+    pub fn solve<S: DiscreteObservation, AC: DiscreteAction>(
+        &self,
+        model: &impl TabularModel<S, AC>,
+    ) -> DynamicProgrammingReport<S, AC> {
+        let mut state_values = vec![0.; S::COUNT];
+        let mut policy = vec![AC::ACTIONS[0]; S::COUNT];
+        let mut sweeps = 0;
+        let mut improvements = 0;
+
+        let (action_values, converged) = loop {
+            let (evaluation_sweeps, evaluation_converged) = evaluate_policy(
+                model,
+                &mut state_values,
+                |action, observation: &S| {
+                    if policy[observation.checked_index()].eq(action) {
+                        1.
+                    } else {
+                        0.
+                    }
+                },
+                (
+                    self.discount_factor,
+                    self.tolerance,
+                    self.max_sweeps,
+                    self.sweep,
+                ),
+            );
+            sweeps += evaluation_sweeps;
+
+            let action_values = action_values(model, &state_values, self.discount_factor);
+
+            // Policy improvement, only changes actions that are better by more than the
+            // tolerance, so policies with equally good actions don't cycle
+            let mut policy_stable = true;
+            for (observation, current_action) in S::OBSERVATIONS.iter().zip(policy.iter_mut()) {
+                let greedy_action = action_values.greedy_action(observation);
+                if action_values[(observation, &greedy_action)]
+                    > action_values[(observation, &*current_action)] + self.tolerance
+                {
+                    *current_action = greedy_action;
+                    policy_stable = false;
+                }
+            }
+
+            if policy_stable {
+                break (action_values, evaluation_converged);
+            }
+            improvements += 1;
+            if improvements >= self.max_improvements {
+                break (action_values, false);
+            }
+        };
+
+        DynamicProgrammingReport {
+            action_values,
+            state_values: VTable::from_values(state_values),
+            sweeps,
+            converged,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{
+    action::DiscreteAction,
+    model::TabularModel,
+    observation::DiscreteObservation,
+    reinforcement_learning::dynamic_programming::{
+        action_values, one_step_lookahead, sweep_state_values, DynamicProgrammingReport, Sweep,
+    },
+    value_table::VTable,
+};
+
+/// Value iteration, sweeps the values with the Bellman optimality equation
+/// `V(s) = max_a Σ p(s', r | s, a) [r + γ V(s')]`.
+pub struct ValueIteration {
+    discount_factor: f64,
+    tolerance: f64,
+    max_sweeps: usize,
+    sweep: Sweep,
+}
+
+impl ValueIteration {
+    /// # Arguments
+    /// `tolerance`: Iteration stops when no value changes more than `tolerance` on a sweep</br>
+    /// `max_sweeps`: Iteration stops after `max_sweeps` even if values didn't converge
+    #[must_use]
+    pub fn new(discount_factor: f64, tolerance: f64, max_sweeps: usize) -> Self {
+        Self {
+            discount_factor,
+            tolerance,
+            max_sweeps,
+            sweep: Sweep::Synchronous,
+        }
+    }
+
+    /// Replaces the [`Sweep`] used, [`Sweep::Synchronous`] by default.
+    #[must_use]
+    pub fn with_sweep(mut self, sweep: Sweep) -> Self {
+        self.sweep = sweep;
+        self
+    }
+
+    /// Optimal values of `model`, the optimal policy is greedy with respect to
+    /// [`DynamicProgrammingReport::action_values`].
+    pub fn solve<S: DiscreteObservation, AC: DiscreteAction>(
+        &self,
+        model: &impl TabularModel<S, AC>,
+    ) -> DynamicProgrammingReport<S, AC> {
+        let mut state_values = vec![0.; S::COUNT];
+        let mut sweeps = 0;
+        let mut converged = false;
+
+        while sweeps < self.max_sweeps && !converged {
+            let delta = sweep_state_values(
+                &mut state_values,
+                self.sweep,
+                |observation: &S, state_values| {
+                    AC::ACTIONS
+                        .iter()
+                        .map(|action| {
+                            one_step_lookahead(
+                                model,
+                                observation,
+                                action,
+                                state_values,
+                                self.discount_factor,
+                            )
+                        })
+                        .max_by(f64::total_cmp)
+                        .expect("There must be a action with maximum value.")
+                },
+            );
+            sweeps += 1;
+            converged = delta < self.tolerance;
+        }
+
+        DynamicProgrammingReport {
+            action_values: action_values(model, &state_values, self.discount_factor),
+            state_values: VTable::from_values(state_values),
+            sweeps,
+            converged,
+        }
+    }
+}
@@ -1,33 +1,99 @@
 use crate::{
-    action::DiscreteAction, agent::Agent, environment::Environment,
-    observation::DiscreteObservation, trajectory::Trajectory,
+    agent::Agent,
+    environment::Environment,
+    space::SpaceElement,
+    trajectory::Trajectory,
+    value_table::{ObservationActionTable, QTable, Storage},
 };
 
+pub mod average_reward;
+pub mod dynamic_programming;
 pub mod monte_carlo;
+pub mod prediction;
+mod stopping_criterion;
 pub mod temporal_difference;
+mod training_observer;
+mod training_report;
+
+pub use self::{
+    stopping_criterion::StoppingCriterion, training_observer::TrainingObserver,
+    training_report::TrainingReport,
+};
 
 pub trait PolicyEstimator {
     type Environment: crate::environment::Environment;
+    /// [`Storage`] of the tables of values learned by the estimator.
+    type Storage: Storage;
+
+    /// [`StoppingCriterion`] that ends the search.
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion;
+
+    /// Replaces the [`StoppingCriterion`] of the search.
+    ///
+    /// # Panics
+    /// The search of a continuing task, like the
+    /// [average reward](crate::reinforcement_learning::average_reward) estimators,
+    /// panics if the criterion has a [`StoppingCriterion::MaxEpisodes`].
+    #[must_use]
+    fn with_stopping_criterion(mut self, stopping_criterion: StoppingCriterion) -> Self
+    where
+        Self: Sized,
+    {
+        *self.stopping_criterion_mut() = stopping_criterion;
+        self
+    }
 
+    #[allow(clippy::type_complexity)]
     fn policy_search(
         self,
         environment: &mut Self::Environment,
         agent: &mut <Self::Environment as Environment>::Agent,
-    );
+    ) -> TrainingReport<
+        <<Self::Environment as Environment>::Agent as Agent>::Observation,
+        <<Self::Environment as Environment>::Agent as Agent>::Action,
+        Self::Storage,
+    >
+    where
+        Self: Sized,
+    {
+        self.policy_search_with_observer(environment, agent, &mut ())
+    }
+
+    /// Same as [`PolicyEstimator::policy_search`], but notifies `observer` of the
+    /// events of the training.
+    #[allow(clippy::type_complexity)]
+    fn policy_search_with_observer(
+        self,
+        environment: &mut Self::Environment,
+        agent: &mut <Self::Environment as Environment>::Agent,
+        observer: &mut dyn TrainingObserver<
+            <<Self::Environment as Environment>::Agent as Agent>::Observation,
+            <<Self::Environment as Environment>::Agent as Agent>::Action,
+        >,
+    ) -> TrainingReport<
+        <<Self::Environment as Environment>::Agent as Agent>::Observation,
+        <<Self::Environment as Environment>::Agent as Agent>::Action,
+        Self::Storage,
+    >
+    where
+        Self: Sized;
 
     /// Calculates the returns of a list of rewards using the equation
     /// `G{t,i} = r{t,i} + γ r{t+1,i} + γ^2 r{t+2,i} + ... +  γ^{Ti-1} r{Ti,i}` where
     /// `i` is an episode, `t` is the time step of the episode `i`,
     /// `Ti` is the last step of the episode `i`, and `γ` is the return discount.
+    ///
+    /// # Arguments
+    /// `bootstrap_value`: Value of the observation after the last step, added to the returns
+    /// discounted by `γ^{Ti-t+1}`. Zero unless the episode was truncated
     #[allow(clippy::type_complexity)]
     fn discounted_return(
-        trajectory: &Vec<
-            Trajectory<
-                <<Self::Environment as Environment>::Agent as Agent>::Observation,
-                <<Self::Environment as Environment>::Agent as Agent>::Action,
-            >,
-        >,
+        trajectory: &[Trajectory<
+            <<Self::Environment as Environment>::Agent as Agent>::Observation,
+            <<Self::Environment as Environment>::Agent as Agent>::Action,
+        >],
         return_discount: f64,
+        bootstrap_value: f64,
         episode_returns: &mut Vec<f64>,
     ) {
         episode_returns.resize(trajectory.len() - 1, 0.);
@@ -40,10 +106,13 @@ pub trait PolicyEstimator {
                     action: _,
                     reward,
                 } => Some(*reward),
-                Trajectory::Final { observation: _ } => None,
+                Trajectory::Final {
+                    observation: _,
+                    episode_end: _,
+                } => None,
             })
             .rev()
-            .scan(0., |prev_return, reward| {
+            .scan(bootstrap_value, |prev_return, reward| {
                 let current_return = reward + return_discount * *prev_return;
                 *prev_return = current_return;
                 Some(current_return)
@@ -56,20 +125,31 @@ pub trait PolicyEstimator {
 }
 
 trait DiscretePolicyEstimator<
-    AC: DiscreteAction,
-    S: DiscreteObservation,
+    AC: SpaceElement,
+    S: SpaceElement,
     AG: Agent<Action = AC, Observation = S>,
     E: Environment<Agent = AG>,
 >: PolicyEstimator<Environment = E>
 {
-    fn tabular_index(action: &AC, observation: &S) -> usize {
-        observation.index() * AC::ACTIONS.len() + action.index()
+    /// Propagates the values and visits of all the actions of `observation` to the
+    /// policy of the `agent`, in a single call.
+    fn improve_policy<A: Agent<Action = AC, Observation = S>>(
+        agent: &mut A,
+        observation: &S,
+        action_value: &QTable<S, AC, Self::Storage>,
+        visit_count: &ObservationActionTable<S, AC, usize, Self::Storage>,
+    ) {
+        agent.policy_improvement_with_visits(
+            observation,
+            action_value.observation_values(observation),
+            visit_count.observation_values(observation),
+        );
     }
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
         E: Environment<Agent = AG>,
         PE: ?Sized + PolicyEstimator<Environment = E>,
@@ -1,69 +1,101 @@
 use std::marker::PhantomData;
 
 use crate::{
-    action::DiscreteAction,
     agent::Agent,
-    environment::EpisodicEnvironment,
-    observation::DiscreteObservation,
+    environment::{DiscreteEnvironment, EpisodicEnvironment},
     reinforcement_learning::{
         monte_carlo::{MonteCarlo, MonteCarloSearchState},
-        DiscretePolicyEstimator, PolicyEstimator,
+        DiscretePolicyEstimator, PolicyEstimator, StoppingCriterion, TrainingObserver,
+        TrainingReport,
     },
+    schedule::StepSize,
+    space::SpaceElement,
     trajectory::Trajectory,
+    value_table::{Dense, Storage},
 };
 
-pub struct ConstantAlphaMonteCarlo<E: EpisodicEnvironment> {
-    alpha: f64,
+pub struct ConstantAlphaMonteCarlo<E: EpisodicEnvironment, ST: Storage = Dense> {
+    step_size: StepSize,
     return_discount: f64,
-    episodes: usize,
-    phantom_environment: PhantomData<E>,
+    stopping_criterion: StoppingCriterion,
+    phantom_environment: PhantomData<(E, ST)>,
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > ConstantAlphaMonteCarlo<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > ConstantAlphaMonteCarlo<E, ST>
 {
     #[must_use]
     pub fn new(alpha: f64, return_discount: f64, episodes: usize) -> Self {
         Self {
-            alpha,
+            step_size: StepSize::Constant(alpha),
             return_discount,
-            episodes,
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episodes),
             phantom_environment: PhantomData,
         }
     }
+
+    /// Replaces the [`StepSize`] of the updates, which is `alpha` by default.
+    #[must_use]
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = step_size;
+        self
+    }
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > PolicyEstimator for ConstantAlphaMonteCarlo<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > PolicyEstimator for ConstantAlphaMonteCarlo<E, ST>
 {
     type Environment = E;
+    type Storage = ST;
 
-    fn policy_search(self, environment: &mut Self::Environment, agent: &mut E::Agent) {
-        self.monte_carlo_policy_search(environment, agent, self.return_discount, self.episodes);
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn policy_search_with_observer(
+        self,
+        environment: &mut Self::Environment,
+        agent: &mut E::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
+        self.monte_carlo_policy_search(
+            environment,
+            agent,
+            observer,
+            self.return_discount,
+            &self.stopping_criterion,
+        )
     }
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > MonteCarlo<AC, S, AG, E> for ConstantAlphaMonteCarlo<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > MonteCarlo<AC, S, AG, E> for ConstantAlphaMonteCarlo<E, ST>
 {
+    fn step_size(&self) -> StepSize {
+        self.step_size
+    }
+
     fn step_update(
         &self,
         agent: &mut AG,
         step: &Trajectory<S, AC>,
         step_return: &f64,
-        monte_carlo_search_state: MonteCarloSearchState,
+        monte_carlo_search_state: MonteCarloSearchState<S, AC, ST>,
     ) -> f64 {
         if let Trajectory::Step {
             action,
@@ -71,8 +103,14 @@ impl<
             reward: _,
         } = step
         {
-            let markov_reward_process_index = Self::tabular_index(action, observation);
+            let markov_reward_process_index = monte_carlo_search_state
+                .visit_count
+                .tabular_index(observation, action);
             monte_carlo_search_state.visit_count[markov_reward_process_index] += 1;
+            let alpha = monte_carlo_search_state.step_sizes.next(
+                markov_reward_process_index,
+                monte_carlo_search_state.visit_count[markov_reward_process_index],
+            );
 
             let old_observation_value =
                 monte_carlo_search_state.observation_values[markov_reward_process_index];
@@ -80,19 +118,17 @@ impl<
             // Update state-action value
             monte_carlo_search_state.observation_values[markov_reward_process_index] =
                 monte_carlo_search_state.observation_values[markov_reward_process_index]
-                    + self.alpha
+                    + alpha
                         * (step_return
                             - monte_carlo_search_state.observation_values
                                 [markov_reward_process_index]);
             // Propagate change to policy
-            AC::ACTIONS.iter().for_each(|discrete_action| {
-                let tabular_index = Self::tabular_index(discrete_action, observation);
-                agent.policy_improvemnt(
-                    discrete_action,
-                    observation,
-                    monte_carlo_search_state.observation_values[tabular_index],
-                );
-            });
+            Self::improve_policy(
+                agent,
+                observation,
+                monte_carlo_search_state.observation_values,
+                monte_carlo_search_state.visit_count,
+            );
 
             (old_observation_value
                 - monte_carlo_search_state.observation_values[markov_reward_process_index])
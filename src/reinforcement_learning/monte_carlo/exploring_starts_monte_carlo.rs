@@ -0,0 +1,158 @@
+use std::marker::PhantomData;
+
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, Environment, ExploringStartsEnvironment},
+    random_number_generator::RandomNumberGeneratorFacade,
+    reinforcement_learning::{
+        monte_carlo::{record_trajectory, MonteCarlo, MonteCarloSearchState},
+        stopping_criterion::TrainingProgress,
+        PolicyEstimator, StoppingCriterion, TrainingObserver, TrainingReport,
+    },
+    space::SpaceElement,
+    trajectory::Trajectory,
+    value_table::{Dense, Storage},
+};
+
+/// Observation-action pair that an episode of `E` starts on.
+type Start<E> = (
+    <<E as Environment>::Agent as Agent>::Observation,
+    <<E as Environment>::Agent as Agent>::Action,
+);
+
+/// First visit Monte Carlo with exploring starts, each episode starts on an
+/// observation-action pair chosen uniformly, so every pair keeps being sampled
+/// even when the agent is greedy.
+pub struct ExploringStartsMonteCarlo<
+    E: ExploringStartsEnvironment,
+    RNG: RandomNumberGeneratorFacade,
+    ST: Storage = Dense,
+> {
+    return_discount: f64,
+    stopping_criterion: StoppingCriterion,
+    random_number_generator: RNG,
+    /// Pairs that episodes can start on, listed at the start of the search
+    starts: Vec<Start<E>>,
+    phantom_environment: PhantomData<(E, ST)>,
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: ExploringStartsEnvironment<Agent = AG> + DiscreteEnvironment,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > ExploringStartsMonteCarlo<E, RNG, ST>
+{
+    /// # Arguments
+    /// `random_number_generator`: Chooses the observation-action pair each episode starts on
+    #[must_use]
+    pub fn new(return_discount: f64, episodes: usize, random_number_generator: RNG) -> Self {
+        Self {
+            return_discount,
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episodes),
+            random_number_generator,
+            starts: vec![],
+            phantom_environment: PhantomData,
+        }
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: ExploringStartsEnvironment<Agent = AG> + DiscreteEnvironment,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > PolicyEstimator for ExploringStartsMonteCarlo<E, RNG, ST>
+{
+    type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    /// # Panics
+    /// Panics if no legal pair of the [`DiscreteEnvironment`] starts an episode that
+    /// isn't over right away.
+    fn policy_search_with_observer(
+        mut self,
+        environment: &mut Self::Environment,
+        agent: &mut E::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
+        let action_mask = environment.action_mask();
+        self.starts = environment
+            .observation_space()
+            .iter()
+            // Terminal observations would start an episode without steps
+            .filter(|observation| environment.reset_to(observation))
+            .flat_map(|observation| {
+                action_mask
+                    .legal_actions(&observation)
+                    .map(|action| (observation, action))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert!(
+            !self.starts.is_empty(),
+            "Exploring starts need a legal action on an observation that doesn't end the episode."
+        );
+
+        self.monte_carlo_policy_search(
+            environment,
+            agent,
+            observer,
+            self.return_discount,
+            &self.stopping_criterion,
+        )
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: ExploringStartsEnvironment<Agent = AG> + DiscreteEnvironment,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > MonteCarlo<AC, S, AG, E> for ExploringStartsMonteCarlo<E, RNG, ST>
+{
+    /// Starts the episode on an observation-action pair sampled uniformly over the
+    /// legal pairs of the [`DiscreteEnvironment`] whose observation doesn't end the
+    /// episode right away.
+    fn episode_trajectory(
+        &self,
+        environment: &mut E,
+        agent: &mut AG,
+        observer: &mut dyn TrainingObserver<S, AC>,
+        progress: &TrainingProgress,
+        trajectory: &mut Vec<Trajectory<S, AC>>,
+    ) {
+        let (observation, action) = self.starts[self
+            .random_number_generator
+            .random_range(0..self.starts.len())];
+        environment.reset_to(&observation);
+        record_trajectory(
+            environment,
+            agent,
+            observer,
+            progress,
+            trajectory,
+            Some(action),
+        );
+    }
+
+    fn step_update(
+        &self,
+        agent: &mut AG,
+        step: &Trajectory<S, AC>,
+        step_return: &f64,
+        monte_carlo_search_state: MonteCarloSearchState<S, AC, ST>,
+    ) -> f64 {
+        self.first_visit_step_update(agent, step, step_return, monte_carlo_search_state)
+    }
+}
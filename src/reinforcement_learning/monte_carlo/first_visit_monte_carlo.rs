@@ -1,101 +1,87 @@
 use std::marker::PhantomData;
 
 use crate::{
-    action::DiscreteAction,
     agent::Agent,
-    environment::EpisodicEnvironment,
-    observation::DiscreteObservation,
+    environment::{DiscreteEnvironment, EpisodicEnvironment},
     reinforcement_learning::{
         monte_carlo::{MonteCarlo, MonteCarloSearchState},
-        DiscretePolicyEstimator, PolicyEstimator,
+        PolicyEstimator, StoppingCriterion, TrainingObserver, TrainingReport,
     },
+    space::SpaceElement,
     trajectory::Trajectory,
+    value_table::{Dense, Storage},
 };
 
-pub struct FirstVisitMonteCarlo<E: EpisodicEnvironment> {
+pub struct FirstVisitMonteCarlo<E: EpisodicEnvironment, ST: Storage = Dense> {
     return_discount: f64,
-    episodes: usize,
-    phantom_environment: PhantomData<E>,
+    stopping_criterion: StoppingCriterion,
+    phantom_environment: PhantomData<(E, ST)>,
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > FirstVisitMonteCarlo<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > FirstVisitMonteCarlo<E, ST>
 {
     #[must_use]
     pub fn new(return_discount: f64, episodes: usize) -> Self {
         Self {
             return_discount,
-            episodes,
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episodes),
             phantom_environment: PhantomData,
         }
     }
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > PolicyEstimator for FirstVisitMonteCarlo<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > PolicyEstimator for FirstVisitMonteCarlo<E, ST>
 {
     type Environment = E;
+    type Storage = ST;
 
-    fn policy_search(self, environment: &mut Self::Environment, agent: &mut E::Agent) {
-        self.monte_carlo_policy_search(environment, agent, self.return_discount, self.episodes)
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn policy_search_with_observer(
+        self,
+        environment: &mut Self::Environment,
+        agent: &mut E::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
+        self.monte_carlo_policy_search(
+            environment,
+            agent,
+            observer,
+            self.return_discount,
+            &self.stopping_criterion,
+        )
     }
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > MonteCarlo<AC, S, AG, E> for FirstVisitMonteCarlo<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > MonteCarlo<AC, S, AG, E> for FirstVisitMonteCarlo<E, ST>
 {
     fn step_update(
         &self,
         agent: &mut AG,
         step: &Trajectory<S, AC>,
         step_return: &f64,
-        monte_carlo_search_state: MonteCarloSearchState,
+        monte_carlo_search_state: MonteCarloSearchState<S, AC, ST>,
     ) -> f64 {
-        if let Trajectory::Step {
-            observation,
-            action,
-            reward: _,
-        } = step
-        {
-            let markov_reward_process_index = Self::tabular_index(action, observation);
-            let old_observation_value =
-                monte_carlo_search_state.observation_values[markov_reward_process_index];
-            if !monte_carlo_search_state.visited[markov_reward_process_index] {
-                monte_carlo_search_state.visited[markov_reward_process_index] = true;
-                monte_carlo_search_state.visit_count[markov_reward_process_index] += 1;
-                monte_carlo_search_state.total_returns[markov_reward_process_index] += step_return;
-
-                // Update state-action value
-                monte_carlo_search_state.observation_values[markov_reward_process_index] =
-                    monte_carlo_search_state.total_returns[markov_reward_process_index]
-                        / monte_carlo_search_state.visit_count[markov_reward_process_index] as f64;
-                // Propagate change to policy
-                AC::ACTIONS.iter().for_each(|discrete_action| {
-                    let tabular_index = Self::tabular_index(discrete_action, observation);
-                    agent.policy_improvemnt(
-                        discrete_action,
-                        observation,
-                        monte_carlo_search_state.observation_values[tabular_index],
-                    );
-                });
-            }
-            (old_observation_value
-                - monte_carlo_search_state.observation_values[markov_reward_process_index])
-                .powi(2)
-        } else {
-            panic!("The final step of an Episode should not be included into the Trajectory.");
-        }
+        self.first_visit_step_update(agent, step, step_return, monte_carlo_search_state)
     }
 }
@@ -1,67 +1,101 @@
 use std::marker::PhantomData;
 
 use crate::{
-    action::DiscreteAction,
     agent::Agent,
-    environment::EpisodicEnvironment,
-    observation::DiscreteObservation,
+    environment::{DiscreteEnvironment, EpisodicEnvironment},
     reinforcement_learning::{
         monte_carlo::{MonteCarlo, MonteCarloSearchState},
-        DiscretePolicyEstimator, PolicyEstimator,
+        DiscretePolicyEstimator, PolicyEstimator, StoppingCriterion, TrainingObserver,
+        TrainingReport,
     },
+    schedule::StepSize,
+    space::SpaceElement,
     trajectory::Trajectory,
+    value_table::{Dense, Storage},
 };
 
-pub struct IncrementalMonteCarlo<E: EpisodicEnvironment> {
+pub struct IncrementalMonteCarlo<E: EpisodicEnvironment, ST: Storage = Dense> {
     return_discount: f64,
-    episodes: usize,
-    phantom_environment: PhantomData<E>,
+    step_size: StepSize,
+    stopping_criterion: StoppingCriterion,
+    phantom_environment: PhantomData<(E, ST)>,
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > IncrementalMonteCarlo<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > IncrementalMonteCarlo<E, ST>
 {
     #[must_use]
     pub fn new(return_discount: f64, episodes: usize) -> Self {
         Self {
             return_discount,
-            episodes,
+            step_size: StepSize::SampleAverage,
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episodes),
             phantom_environment: PhantomData,
         }
     }
+
+    /// Replaces the [`StepSize`] of the updates, which is `1 / n` by default.
+    #[must_use]
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = step_size;
+        self
+    }
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > PolicyEstimator for IncrementalMonteCarlo<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > PolicyEstimator for IncrementalMonteCarlo<E, ST>
 {
     type Environment = E;
+    type Storage = ST;
 
-    fn policy_search(self, environment: &mut Self::Environment, agent: &mut E::Agent) {
-        self.monte_carlo_policy_search(environment, agent, self.return_discount, self.episodes)
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn policy_search_with_observer(
+        self,
+        environment: &mut Self::Environment,
+        agent: &mut E::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
+        self.monte_carlo_policy_search(
+            environment,
+            agent,
+            observer,
+            self.return_discount,
+            &self.stopping_criterion,
+        )
     }
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > MonteCarlo<AC, S, AG, E> for IncrementalMonteCarlo<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > MonteCarlo<AC, S, AG, E> for IncrementalMonteCarlo<E, ST>
 {
+    fn step_size(&self) -> StepSize {
+        self.step_size
+    }
+
     fn step_update(
         &self,
         agent: &mut AG,
         step: &Trajectory<S, AC>,
         step_return: &f64,
-        monte_carlo_search_state: MonteCarloSearchState,
+        monte_carlo_search_state: MonteCarloSearchState<S, AC, ST>,
     ) -> f64 {
         if let Trajectory::Step {
             observation,
@@ -69,13 +103,17 @@ impl<
             reward: _,
         } = step
         {
-            let markov_reward_process_index = Self::tabular_index(action, observation);
+            let markov_reward_process_index = monte_carlo_search_state
+                .visit_count
+                .tabular_index(observation, action);
             monte_carlo_search_state.visit_count[markov_reward_process_index] += 1;
 
             let old_observation_value =
                 monte_carlo_search_state.observation_values[markov_reward_process_index];
-            let alpha =
-                1. / monte_carlo_search_state.visit_count[markov_reward_process_index] as f64;
+            let alpha = monte_carlo_search_state.step_sizes.next(
+                markov_reward_process_index,
+                monte_carlo_search_state.visit_count[markov_reward_process_index],
+            );
 
             // Update state-action value
             monte_carlo_search_state.observation_values[markov_reward_process_index] =
@@ -85,14 +123,12 @@ impl<
                             - monte_carlo_search_state.observation_values
                                 [markov_reward_process_index]);
             // Propagate change to policy
-            AC::ACTIONS.iter().for_each(|discrete_action| {
-                let tabular_index = Self::tabular_index(discrete_action, observation);
-                agent.policy_improvemnt(
-                    discrete_action,
-                    observation,
-                    monte_carlo_search_state.observation_values[tabular_index],
-                );
-            });
+            Self::improve_policy(
+                agent,
+                observation,
+                monte_carlo_search_state.observation_values,
+                monte_carlo_search_state.visit_count,
+            );
 
             (old_observation_value
                 - monte_carlo_search_state.observation_values[markov_reward_process_index])
@@ -1,34 +1,46 @@
 mod constant_alpha_monte_carlo;
 mod every_visit_monte_carlo;
+mod exploring_starts_monte_carlo;
 mod first_visit_monte_carlo;
 mod incremental_monte_carlo;
+mod off_policy_monte_carlo;
 
 pub use self::{
     constant_alpha_monte_carlo::ConstantAlphaMonteCarlo,
-    every_visit_monte_carlo::EveryVisitMonteCarlo, first_visit_monte_carlo::FirstVisitMonteCarlo,
+    every_visit_monte_carlo::EveryVisitMonteCarlo,
+    exploring_starts_monte_carlo::ExploringStartsMonteCarlo,
+    first_visit_monte_carlo::FirstVisitMonteCarlo,
     incremental_monte_carlo::IncrementalMonteCarlo,
+    off_policy_monte_carlo::{ImportanceSampling, OffPolicyMonteCarlo},
 };
 
-use std::collections::VecDeque;
-
 use crate::{
-    action::DiscreteAction, agent::Agent, environment::EpisodicEnvironment,
-    observation::DiscreteObservation, reinforcement_learning::PolicyEstimator,
+    agent::Agent,
+    environment::{DiscreteEnvironment, EpisodeEnd, EpisodicEnvironment},
+    reinforcement_learning::{
+        stopping_criterion::{EpisodeState, TrainingProgress},
+        DiscretePolicyEstimator, PolicyEstimator, StoppingCriterion, TrainingObserver,
+        TrainingReport,
+    },
+    schedule::{StepSize, StepSizes},
+    space::{ActionMask, SpaceElement},
     trajectory::Trajectory,
+    value_table::{ObservationActionTable, QTable, Storage},
 };
 
-struct MonteCarloSearchState<'a> {
-    visited: &'a mut [bool],
-    visit_count: &'a mut [usize],
-    total_returns: &'a mut [f64],
-    observation_values: &'a mut [f64],
+struct MonteCarloSearchState<'a, S, AC, ST: Storage> {
+    visited: &'a mut ObservationActionTable<S, AC, bool, ST>,
+    visit_count: &'a mut ObservationActionTable<S, AC, usize, ST>,
+    total_returns: &'a mut QTable<S, AC, ST>,
+    observation_values: &'a mut QTable<S, AC, ST>,
+    step_sizes: &'a mut StepSizes<ST>,
 }
 
 trait MonteCarlo<
-    AC: DiscreteAction,
-    S: DiscreteObservation,
+    AC: SpaceElement,
+    S: SpaceElement,
     AG: Agent<Action = AC, Observation = S>,
-    E: EpisodicEnvironment<Agent = AG>,
+    E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
 >: PolicyEstimator<Environment = E>
 {
     /// Updates the value of a Markov Reward Process state
@@ -47,40 +59,140 @@ trait MonteCarlo<
         agent: &mut AG,
         step: &Trajectory<S, AC>,
         step_return: &f64,
-        monte_carlo_search_state: MonteCarloSearchState,
+        monte_carlo_search_state: MonteCarloSearchState<S, AC, Self::Storage>,
     ) -> f64;
 
+    /// [`MonteCarlo::step_update`] that only takes into account the first visit to each
+    /// observation-action pair of an episode, averaging their returns.
+    fn first_visit_step_update(
+        &self,
+        agent: &mut AG,
+        step: &Trajectory<S, AC>,
+        step_return: &f64,
+        monte_carlo_search_state: MonteCarloSearchState<S, AC, Self::Storage>,
+    ) -> f64 {
+        if let Trajectory::Step {
+            observation,
+            action,
+            reward: _,
+        } = step
+        {
+            let markov_reward_process_index = monte_carlo_search_state
+                .visit_count
+                .tabular_index(observation, action);
+            let old_observation_value =
+                monte_carlo_search_state.observation_values[markov_reward_process_index];
+            if !monte_carlo_search_state.visited[markov_reward_process_index] {
+                monte_carlo_search_state.visited[markov_reward_process_index] = true;
+                monte_carlo_search_state.visit_count[markov_reward_process_index] += 1;
+                monte_carlo_search_state.total_returns[markov_reward_process_index] += step_return;
+
+                // Update state-action value
+                monte_carlo_search_state.observation_values[markov_reward_process_index] =
+                    monte_carlo_search_state.total_returns[markov_reward_process_index]
+                        / monte_carlo_search_state.visit_count[markov_reward_process_index] as f64;
+                // Propagate change to policy
+                Self::improve_policy(
+                    agent,
+                    observation,
+                    monte_carlo_search_state.observation_values,
+                    monte_carlo_search_state.visit_count,
+                );
+            }
+            (old_observation_value
+                - monte_carlo_search_state.observation_values[markov_reward_process_index])
+                .powi(2)
+        } else {
+            panic!("The final step of an Episode should not be included into the Trajectory.");
+        }
+    }
+
+    /// [`StepSize`] of the updates, for the estimators that don't average the returns directly.
+    fn step_size(&self) -> StepSize {
+        StepSize::SampleAverage
+    }
+
+    /// Runs an episode, storing the steps on `trajectory`.
+    fn episode_trajectory(
+        &self,
+        environment: &mut E,
+        agent: &mut AG,
+        observer: &mut dyn TrainingObserver<S, AC>,
+        progress: &TrainingProgress,
+        trajectory: &mut Vec<Trajectory<S, AC>>,
+    ) {
+        generate_trajectory(environment, agent, observer, progress, trajectory);
+    }
+
     fn monte_carlo_policy_search(
         &self,
         environment: &mut E,
         agent: &mut AG,
+        observer: &mut dyn TrainingObserver<S, AC>,
         return_discount: f64,
-        iteration_limit: usize,
-    ) {
-        let mut visited: Vec<bool> = vec![false; S::OBSERVATIONS.len() * AC::ACTIONS.len()];
-        let mut visit_count = vec![0usize; S::OBSERVATIONS.len() * AC::ACTIONS.len()];
-        let mut total_returns = vec![0.0f64; S::OBSERVATIONS.len() * AC::ACTIONS.len()];
-        let mut observation_values = vec![0.0f64; S::OBSERVATIONS.len() * AC::ACTIONS.len()];
+        stopping_criterion: &StoppingCriterion,
+    ) -> TrainingReport<S, AC, Self::Storage> {
+        let observation_space = environment.observation_space();
+        let action_space = environment.action_space();
+        let action_mask = environment.action_mask();
+        let mut step_sizes = StepSizes::new(
+            self.step_size(),
+            observation_space.len(),
+            action_space.len(),
+        );
+        let mut visit_count = ObservationActionTable::<S, AC, usize, Self::Storage>::with_spaces(
+            observation_space.clone(),
+            action_space.clone(),
+            0,
+        );
+        let mut total_returns = QTable::<S, AC, Self::Storage>::with_spaces(
+            observation_space.clone(),
+            action_space.clone(),
+            0.,
+        );
+        let mut observation_values = total_returns.clone();
+        // Only the pairs of the trajectory are flagged, so they are the only ones reset
+        let mut visited = ObservationActionTable::<S, AC, bool, Self::Storage>::with_spaces(
+            observation_space.clone(),
+            action_space.clone(),
+            false,
+        );
 
         let mut trajectory = vec![];
         let mut episode_returns = vec![];
 
-        let mut episode = 0usize;
-        let mut episode_variation_window = VecDeque::from_iter([f64::MAX; 5]);
-        while episode_variation_window
-            .iter()
-            .any(|ep_v| ep_v > &f64::EPSILON)
-            && episode < iteration_limit
-        {
-            episode += 1;
-            visited.fill(false);
+        let mut progress = TrainingProgress::new(stopping_criterion);
+        let stopped_by = loop {
+            if let Some(stopped_by) = progress.stopping_criterion() {
+                break stopped_by;
+            }
+            observer.on_episode_start(progress.episodes() + 1);
 
             let mut episode_variation = 0.;
 
-            Self::generate_trajectory(environment, agent, &mut trajectory);
-            Self::discounted_return(&trajectory, return_discount, &mut episode_returns);
+            self.episode_trajectory(environment, agent, observer, &progress, &mut trajectory);
+            Self::discounted_return(
+                &trajectory,
+                return_discount,
+                bootstrap_value(&observation_values, &action_mask, &trajectory),
+                &mut episode_returns,
+            );
 
             for (step, step_return) in trajectory.iter().zip(episode_returns.iter()) {
+                let Trajectory::Step {
+                    observation,
+                    action,
+                    reward: _,
+                } = step
+                else {
+                    panic!(
+                        "The final step of an Episode should not be included into the Trajectory."
+                    );
+                };
+                let markov_reward_process_index = visit_count.tabular_index(observation, action);
+                let old_visit_count = visit_count[markov_reward_process_index];
+                let old_value = observation_values[markov_reward_process_index];
+
                 episode_variation += self.step_update(
                     agent,
                     step,
@@ -90,52 +202,177 @@ trait MonteCarlo<
                         visit_count: &mut visit_count,
                         total_returns: &mut total_returns,
                         observation_values: &mut observation_values,
+                        step_sizes: &mut step_sizes,
                     },
                 );
+
+                if visit_count[markov_reward_process_index] != old_visit_count {
+                    observer.on_value_update(
+                        observation,
+                        action,
+                        old_value,
+                        observation_values[markov_reward_process_index],
+                    );
+                }
             }
-            episode_variation_window.pop_front();
-            episode_variation_window.push_back(episode_variation);
+            for step in &trajectory {
+                if let Trajectory::Step {
+                    observation,
+                    action,
+                    reward: _,
+                } = step
+                {
+                    if visited[(observation, action)] {
+                        visited[(observation, action)] = false;
+                    }
+                }
+            }
+            let episode_return = trajectory
+                .iter()
+                .filter_map(|step| match step {
+                    Trajectory::Step {
+                        observation: _,
+                        action: _,
+                        reward,
+                    } => Some(reward),
+                    Trajectory::Final {
+                        observation: _,
+                        episode_end: _,
+                    } => None,
+                })
+                .sum();
+            observer.on_episode_end(episode_return, trajectory.len() - 1, episode_variation);
+            agent.on_episode_end();
+            progress.end_episode(episode_return, trajectory.len() - 1, episode_variation);
+        };
+
+        TrainingReport {
+            action_values: observation_values,
+            visit_counts: visit_count,
+            episodes: progress.episodes(),
+            steps: progress.steps(),
+            converged: stopped_by.is_convergence(),
+            stopped_by,
+            average_reward: None,
         }
+    }
+}
 
-        Self::print_observation_action_pairs(
-            "Observation Visit Count",
-            &visit_count.iter().map(|u| *u as f64).collect::<Vec<_>>(),
-        );
-        Self::print_observation_action_pairs("Action Value Function", &observation_values);
-        println!("Iterated for {} episodes.", episode);
+/// Agent whose episodes are recorded by [`record_trajectory`], either one that learns
+/// and is told when each step ends, or one that only acts.
+pub(super) trait EpisodeAgent<AG> {
+    fn agent(&self) -> &AG;
+
+    /// Called after every step of the episode.
+    fn on_step_end(&mut self);
+}
+
+impl<AG: Agent> EpisodeAgent<AG> for &mut AG {
+    fn agent(&self) -> &AG {
+        self
     }
 
-    fn generate_trajectory(
-        environment: &mut E,
-        agent: &mut E::Agent,
-        trajectory: &mut Vec<Trajectory<S, AC>>,
-    ) {
-        environment.reset_environment();
-        trajectory.clear();
-
-        while let Some(observation) = environment.get_observation(agent) {
-            let action = agent.act(&observation);
-            let reward = environment.receive_action(agent, &action);
-
-            trajectory.push(Trajectory::Step {
-                observation,
-                action,
-                reward,
-            });
-        }
-        trajectory.push(Trajectory::Final {
-            observation: environment.final_observation(agent),
-        });
+    fn on_step_end(&mut self) {
+        (**self).on_step_end();
+    }
+}
+
+/// Agents that only act, like the ones of the prediction estimators, are never
+/// told when the steps end.
+impl<AG: Agent> EpisodeAgent<AG> for &AG {
+    fn agent(&self) -> &AG {
+        self
     }
 
-    fn print_observation_action_pairs(header: &str, list: &[f64]) {
-        println!("{header}");
-        for (acts, s) in list.chunks(AC::ACTIONS.len()).zip(S::OBSERVATIONS) {
-            print!("{s:?} ");
-            for (action, value) in AC::ACTIONS.iter().zip(acts) {
-                print!("[{action:?}; {value}] ");
+    fn on_step_end(&mut self) {}
+}
+
+/// Runs an episode of `environment` with `agent`, storing the steps on `trajectory`.
+pub(super) fn generate_trajectory<
+    AC: SpaceElement,
+    S: SpaceElement,
+    AG: Agent<Action = AC, Observation = S>,
+    E: EpisodicEnvironment<Agent = AG>,
+>(
+    environment: &mut E,
+    agent: impl EpisodeAgent<AG>,
+    observer: &mut dyn TrainingObserver<S, AC>,
+    progress: &TrainingProgress,
+    trajectory: &mut Vec<Trajectory<S, AC>>,
+) {
+    environment.reset_environment();
+    record_trajectory(environment, agent, observer, progress, trajectory, None);
+}
+
+/// Runs the episode from the current state of `environment`, storing the steps on
+/// `trajectory`.
+///
+/// # Arguments
+/// `progress`: Truncates the episode if the step or time budget of the search runs out</br>
+/// `first_action`: Action taken on the first step instead of the one chosen by `agent`
+fn record_trajectory<
+    AC: SpaceElement,
+    S: SpaceElement,
+    AG: Agent<Action = AC, Observation = S>,
+    E: EpisodicEnvironment<Agent = AG>,
+>(
+    environment: &mut E,
+    mut agent: impl EpisodeAgent<AG>,
+    observer: &mut dyn TrainingObserver<S, AC>,
+    progress: &TrainingProgress,
+    trajectory: &mut Vec<Trajectory<S, AC>>,
+    mut first_action: Option<AC>,
+) {
+    trajectory.clear();
+
+    let final_step = loop {
+        let observation = match progress.episode_state(environment, agent.agent(), trajectory.len())
+        {
+            EpisodeState::Ongoing(observation) => observation,
+            EpisodeState::Terminated => {
+                break Trajectory::Final {
+                    observation: environment.final_observation(agent.agent()),
+                    episode_end: EpisodeEnd::Terminated,
+                }
             }
-            println!();
-        }
+            EpisodeState::Truncated(observation) => {
+                break Trajectory::Final {
+                    observation,
+                    episode_end: EpisodeEnd::Truncated,
+                }
+            }
+        };
+        let action = first_action
+            .take()
+            .unwrap_or_else(|| agent.agent().act(&observation));
+        let reward = environment.receive_action(agent.agent(), &action);
+        observer.on_step(&observation, &action, reward);
+        agent.on_step_end();
+
+        trajectory.push(Trajectory::Step {
+            observation,
+            action,
+            reward,
+        });
+    };
+    trajectory.push(final_step);
+}
+
+/// Greedy value of the legal actions of the final observation of `trajectory` if the
+/// episode was truncated, zero otherwise.
+///
+/// Sampling the action of the agent would add variance to the returns and draw from its
+/// random number generator.
+fn bootstrap_value<AC: SpaceElement, S: SpaceElement, ST: Storage>(
+    action_value: &QTable<S, AC, ST>,
+    action_mask: &ActionMask<S, AC>,
+    trajectory: &[Trajectory<S, AC>],
+) -> f64 {
+    match trajectory.last() {
+        Some(Trajectory::Final {
+            observation,
+            episode_end: EpisodeEnd::Truncated,
+        }) => action_value.legal_max_value(observation, action_mask),
+        _ => 0.,
     }
 }
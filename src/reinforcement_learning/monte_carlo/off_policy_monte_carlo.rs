@@ -0,0 +1,265 @@
+use std::marker::PhantomData;
+
+use crate::{
+    agent::{Agent, DiscreteAgent},
+    environment::{DiscreteEnvironment, EpisodeEnd, EpisodicEnvironment},
+    reinforcement_learning::{
+        monte_carlo::generate_trajectory, stopping_criterion::TrainingProgress,
+        DiscretePolicyEstimator, PolicyEstimator, StoppingCriterion, TrainingObserver,
+        TrainingReport,
+    },
+    space::SpaceElement,
+    trajectory::Trajectory,
+    value_table::{Dense, ObservationActionTable, QTable, Storage},
+};
+
+/// How the returns of the behaviour agent are corrected to estimate the values of
+/// the target agent, using the ratios `ρ = π(a|s) / b(a|s)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportanceSampling {
+    /// Averages the returns scaled by the product of the ratios of the rest of the
+    /// episode. Unbiased, but with possibly unbounded variance.
+    Ordinary,
+    /// Averages the returns weighted by the product of the ratios of the rest of the
+    /// episode. Biased, but with much lower variance.
+    Weighted,
+    /// Scales each reward only by the ratios of the actions taken before it,
+    /// `G = r{t+1} + γ ρ{t+1} (r{t+2} + γ ρ{t+2} (...))`. Unbiased, with lower variance
+    /// than [`ImportanceSampling::Ordinary`].
+    PerDecision,
+}
+
+/// Off-policy Monte Carlo, estimates the values of a target agent from the episodes
+/// generated by the behaviour agent given to [`PolicyEstimator::policy_search`].
+///
+/// The behaviour agent must give a positive probability to every action the target
+/// agent may take. When the target is improved, the behaviour agent is improved
+/// with the same values, so an ε-greedy behaviour keeps exploring around the target.
+pub struct OffPolicyMonteCarlo<'a, E: EpisodicEnvironment, TG, ST: Storage = Dense> {
+    target: &'a mut TG,
+    return_discount: f64,
+    importance_sampling: ImportanceSampling,
+    improve_target: bool,
+    stopping_criterion: StoppingCriterion,
+    phantom_environment: PhantomData<(E, ST)>,
+}
+
+impl<
+        'a,
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: DiscreteAgent<AC, S> + Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        TG: DiscreteAgent<AC, S>,
+        ST: Storage,
+    > OffPolicyMonteCarlo<'a, E, TG, ST>
+{
+    /// # Arguments
+    /// `target`: Agent whose values are estimated, it is improved with the values learned
+    #[must_use]
+    pub fn new(
+        target: &'a mut TG,
+        return_discount: f64,
+        episodes: usize,
+        importance_sampling: ImportanceSampling,
+    ) -> Self {
+        Self {
+            target,
+            return_discount,
+            importance_sampling,
+            improve_target: true,
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episodes),
+            phantom_environment: PhantomData,
+        }
+    }
+
+    /// Whether the target and the behaviour agents are improved with the values learned.
+    /// Without improvement the search only predicts the values of the target agent.
+    #[must_use]
+    pub fn with_target_improvement(mut self, improve_target: bool) -> Self {
+        self.improve_target = improve_target;
+        self
+    }
+
+    /// Propagates the values of `observation` to the target and behaviour agents.
+    fn policy_improvement(
+        &mut self,
+        agent: &mut AG,
+        observation: &S,
+        action_value: &QTable<S, AC, ST>,
+        visit_count: &ObservationActionTable<S, AC, usize, ST>,
+    ) {
+        if self.improve_target {
+            Self::improve_policy(self.target, observation, action_value, visit_count);
+            Self::improve_policy(agent, observation, action_value, visit_count);
+        }
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: DiscreteAgent<AC, S> + Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        TG: DiscreteAgent<AC, S>,
+        ST: Storage,
+    > PolicyEstimator for OffPolicyMonteCarlo<'_, E, TG, ST>
+{
+    type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn policy_search_with_observer(
+        mut self,
+        environment: &mut Self::Environment,
+        agent: &mut E::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
+        let mut visit_count = ObservationActionTable::<S, AC, usize, ST>::with_spaces(
+            environment.observation_space(),
+            environment.action_space(),
+            0,
+        );
+        let mut cumulative_weights = QTable::<S, AC, ST>::with_spaces(
+            environment.observation_space(),
+            environment.action_space(),
+            0.,
+        );
+        let mut action_value = cumulative_weights.clone();
+
+        let mut trajectory = vec![];
+        let mut behaviour_probabilities = vec![];
+
+        let stopping_criterion = self.stopping_criterion.clone();
+        let mut progress = TrainingProgress::new(&stopping_criterion);
+        let stopped_by = loop {
+            if let Some(stopped_by) = progress.stopping_criterion() {
+                break stopped_by;
+            }
+            observer.on_episode_start(progress.episodes() + 1);
+
+            let mut episode_variation = 0.;
+            let mut episode_return = 0.;
+
+            generate_trajectory(
+                environment,
+                &mut *agent,
+                observer,
+                &progress,
+                &mut trajectory,
+            );
+            // Probabilities of the behaviour agent that generated the episode, before
+            // it gets improved by the updates
+            behaviour_probabilities.clear();
+            behaviour_probabilities.extend(trajectory.iter().filter_map(|step| match step {
+                Trajectory::Step {
+                    observation,
+                    action,
+                    reward: _,
+                } => Some(agent.action_probability(action, observation)),
+                Trajectory::Final {
+                    observation: _,
+                    episode_end: _,
+                } => None,
+            }));
+
+            // Expected value of the target agent on the observation where the episode
+            // was truncated
+            let mut step_return = match trajectory.last() {
+                Some(Trajectory::Final {
+                    observation,
+                    episode_end: EpisodeEnd::Truncated,
+                }) => action_value
+                    .action_space()
+                    .iter()
+                    .zip(action_value.observation_values(observation))
+                    .map(|(action, value)| {
+                        self.target.action_probability(&action, observation) * value
+                    })
+                    .fold(0., |value, action_value| value + action_value),
+                _ => 0.,
+            };
+            let mut importance_weight = 1.;
+            let mut next_ratio = 1.;
+            // The final step is the last one of the trajectory
+            for (step, behaviour_probability) in trajectory[..trajectory.len() - 1]
+                .iter()
+                .zip(behaviour_probabilities.iter())
+                .rev()
+            {
+                let Trajectory::Step {
+                    observation,
+                    action,
+                    reward,
+                } = step
+                else {
+                    panic!(
+                        "The final step of an Episode should not be included into the Trajectory."
+                    );
+                };
+                episode_return += reward;
+
+                let tabular_index = action_value.tabular_index(observation, action);
+                let old_value = action_value[tabular_index];
+                let (target_return, weight) = match self.importance_sampling {
+                    ImportanceSampling::Ordinary => {
+                        step_return = reward + self.return_discount * step_return;
+                        (importance_weight * step_return, 1.)
+                    }
+                    ImportanceSampling::Weighted => {
+                        step_return = reward + self.return_discount * step_return;
+                        (step_return, importance_weight)
+                    }
+                    ImportanceSampling::PerDecision => {
+                        step_return = reward + self.return_discount * next_ratio * step_return;
+                        (step_return, 1.)
+                    }
+                };
+
+                if weight > 0. {
+                    visit_count[tabular_index] += 1;
+                    cumulative_weights[tabular_index] += weight;
+                    // Update state-action value
+                    action_value[tabular_index] += weight / cumulative_weights[tabular_index]
+                        * (target_return - action_value[tabular_index]);
+                    observer.on_value_update(
+                        observation,
+                        action,
+                        old_value,
+                        action_value[tabular_index],
+                    );
+                    self.policy_improvement(agent, observation, &action_value, &visit_count);
+                    episode_variation += (old_value - action_value[tabular_index]).powi(2);
+                }
+
+                assert!(
+                    *behaviour_probability > 0.,
+                    "The behaviour agent must give a positive probability to the actions it takes."
+                );
+                next_ratio =
+                    self.target.action_probability(action, observation) / behaviour_probability;
+                importance_weight *= next_ratio;
+            }
+
+            observer.on_episode_end(episode_return, trajectory.len() - 1, episode_variation);
+
+            agent.on_episode_end();
+
+            self.target.on_episode_end();
+            progress.end_episode(episode_return, trajectory.len() - 1, episode_variation);
+        };
+
+        TrainingReport {
+            action_values: action_value,
+            visit_counts: visit_count,
+            episodes: progress.episodes(),
+            steps: progress.steps(),
+            converged: stopped_by.is_convergence(),
+            stopped_by,
+            average_reward: None,
+        }
+    }
+}
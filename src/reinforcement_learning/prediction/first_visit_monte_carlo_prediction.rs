@@ -0,0 +1,64 @@
+use std::marker::PhantomData;
+
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, EpisodicEnvironment},
+    reinforcement_learning::{
+        prediction::{monte_carlo_prediction, PredictionReport, ValueEstimator},
+        StoppingCriterion,
+    },
+    space::SpaceElement,
+    value_table::{Dense, Storage},
+};
+
+/// Predicts state values averaging the returns that followed the first visit to
+/// each observation on an episode.
+pub struct FirstVisitMonteCarloPrediction<E: EpisodicEnvironment, ST: Storage = Dense> {
+    return_discount: f64,
+    stopping_criterion: StoppingCriterion,
+    phantom_environment: PhantomData<(E, ST)>,
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > FirstVisitMonteCarloPrediction<E, ST>
+{
+    #[must_use]
+    pub fn new(return_discount: f64, episodes: usize) -> Self {
+        Self {
+            return_discount,
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episodes),
+            phantom_environment: PhantomData,
+        }
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > ValueEstimator for FirstVisitMonteCarloPrediction<E, ST>
+{
+    type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn value_prediction(self, environment: &mut E, agent: &AG) -> PredictionReport<S, ST> {
+        monte_carlo_prediction(
+            environment,
+            agent,
+            self.return_discount,
+            &self.stopping_criterion,
+            true,
+        )
+    }
+}
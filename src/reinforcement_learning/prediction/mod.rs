@@ -0,0 +1,264 @@
+//! Estimators that predict the values of the fixed policy of an agent, without
+//! improving it.
+
+use std::collections::VecDeque;
+
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, Environment, EpisodeEnd, EpisodicEnvironment},
+    reinforcement_learning::{
+        monte_carlo::generate_trajectory,
+        stopping_criterion::{EpisodeState, TrainingProgress},
+        StoppingCriterion,
+    },
+    schedule::{StepSize, StepSizes},
+    space::SpaceElement,
+    trajectory::Trajectory,
+    value_table::{ObservationTable, Storage, StorageTable, VTable, ValueStorage},
+};
+
+// Docs imports
+#[allow(unused_imports)]
+use crate::reinforcement_learning::PolicyEstimator;
+
+mod every_visit_monte_carlo_prediction;
+mod first_visit_monte_carlo_prediction;
+mod n_step_temporal_difference_prediction;
+mod prediction_report;
+mod temporal_difference_lambda_prediction;
+mod temporal_difference_prediction;
+
+pub use self::{
+    every_visit_monte_carlo_prediction::EveryVisitMonteCarloPrediction,
+    first_visit_monte_carlo_prediction::FirstVisitMonteCarloPrediction,
+    n_step_temporal_difference_prediction::NStepTemporalDifferencePrediction,
+    prediction_report::PredictionReport,
+    temporal_difference_lambda_prediction::TemporalDifferenceLambdaPrediction,
+    temporal_difference_prediction::TemporalDifferencePrediction,
+};
+
+/// Counterpart of [`PolicyEstimator`] that only estimates the state values of the
+/// policy of an agent.
+pub trait ValueEstimator {
+    type Environment: EpisodicEnvironment;
+    /// [`Storage`] of the tables of values predicted by the estimator.
+    type Storage: Storage;
+
+    /// [`StoppingCriterion`] that ends the prediction.
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion;
+
+    /// Replaces the [`StoppingCriterion`] of the prediction.
+    #[must_use]
+    fn with_stopping_criterion(mut self, stopping_criterion: StoppingCriterion) -> Self
+    where
+        Self: Sized,
+    {
+        *self.stopping_criterion_mut() = stopping_criterion;
+        self
+    }
+
+    /// Estimates the state values of the policy of `agent`, which is only asked to act,
+    /// and never has [`Agent::policy_improvemnt`] called.
+    fn value_prediction(
+        self,
+        environment: &mut Self::Environment,
+        agent: &<Self::Environment as Environment>::Agent,
+    ) -> PredictionReport<
+        <<Self::Environment as Environment>::Agent as Agent>::Observation,
+        Self::Storage,
+    >;
+}
+
+/// Averages the returns that followed each observation.
+///
+/// # Arguments
+/// `first_visit`: Whether only the first visit to an observation on an episode is averaged
+fn monte_carlo_prediction<
+    AC: SpaceElement,
+    S: SpaceElement,
+    AG: Agent<Action = AC, Observation = S>,
+    E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+    ST: Storage,
+>(
+    environment: &mut E,
+    agent: &AG,
+    return_discount: f64,
+    stopping_criterion: &StoppingCriterion,
+    first_visit: bool,
+) -> PredictionReport<S, ST> {
+    let observation_space = environment.observation_space();
+    // Only the observations of the episode are flagged, so they are the only ones reset
+    let mut visited = StorageTable::<ST, bool>::with_default(observation_space.len(), 1, false);
+    let mut visit_count = StorageTable::<ST, usize>::with_default(observation_space.len(), 1, 0);
+    let mut total_returns = StorageTable::<ST, f64>::with_default(observation_space.len(), 1, 0.);
+    let mut state_values = total_returns.clone();
+
+    let mut trajectory = vec![];
+    let mut episode = vec![];
+    let mut episode_returns = vec![];
+
+    let mut progress = TrainingProgress::new(stopping_criterion);
+    let stopped_by = loop {
+        if let Some(stopped_by) = progress.stopping_criterion() {
+            break stopped_by;
+        }
+        let mut episode_variation = 0.;
+
+        generate_trajectory(environment, agent, &mut (), &progress, &mut trajectory);
+        // Observations and the rewards that followed them
+        episode.clear();
+        episode.extend(trajectory.iter().filter_map(|step| match step {
+            Trajectory::Step {
+                observation,
+                action: _,
+                reward,
+            } => Some((*observation, *reward)),
+            Trajectory::Final {
+                observation: _,
+                episode_end: _,
+            } => None,
+        }));
+        let bootstrap_value = match trajectory.last() {
+            Some(Trajectory::Final {
+                observation,
+                episode_end: EpisodeEnd::Truncated,
+            }) => state_values[observation_space.index_of(observation)],
+            _ => 0.,
+        };
+        episode_returns.clear();
+        episode_returns.extend(episode.iter().rev().scan(
+            bootstrap_value,
+            |step_return, (_, reward)| {
+                *step_return = reward + return_discount * *step_return;
+                Some(*step_return)
+            },
+        ));
+        episode_returns.reverse();
+
+        for ((observation, _), step_return) in episode.iter().zip(episode_returns.iter()) {
+            let index = observation_space.index_of(observation);
+            if first_visit && visited[index] {
+                continue;
+            }
+            visited[index] = true;
+
+            let old_value = state_values[index];
+            visit_count[index] += 1;
+            total_returns[index] += step_return;
+            state_values[index] = total_returns[index] / visit_count[index] as f64;
+            episode_variation += (old_value - state_values[index]).powi(2);
+        }
+
+        for (observation, _) in &episode {
+            let index = observation_space.index_of(observation);
+            if visited[index] {
+                visited[index] = false;
+            }
+        }
+
+        let episode_return = episode.iter().map(|(_, reward)| reward).sum();
+        progress.end_episode(episode_return, episode.len(), episode_variation);
+    };
+
+    PredictionReport {
+        state_values: VTable::from_storage(observation_space.clone(), state_values),
+        visit_counts: ObservationTable::from_storage(observation_space, visit_count),
+        episodes: progress.episodes(),
+        converged: stopped_by.is_convergence(),
+        stopped_by,
+    }
+}
+
+/// Updates the value of each observation towards the `n`-step return
+/// `G{t:t+n} = r{t+1} + γ r{t+2} + ... + γ^{n-1} r{t+n} + γ^n V(s{t+n})`.
+fn n_step_temporal_difference_prediction<
+    AC: SpaceElement,
+    S: SpaceElement,
+    AG: Agent<Action = AC, Observation = S>,
+    E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+    ST: Storage,
+>(
+    environment: &mut E,
+    agent: &AG,
+    (steps, step_size, discount_factor): (usize, StepSize, f64),
+    stopping_criterion: &StoppingCriterion,
+) -> PredictionReport<S, ST> {
+    let observation_space = environment.observation_space();
+    let mut visit_count = StorageTable::<ST, usize>::with_default(observation_space.len(), 1, 0);
+    let mut step_sizes = StepSizes::<ST>::new(step_size, observation_space.len(), 1);
+    let mut state_values = StorageTable::<ST, f64>::with_default(observation_space.len(), 1, 0.);
+
+    let mut update = |state_values: &mut StorageTable<ST, f64>, observation: &S, target: f64| {
+        let index = observation_space.index_of(observation);
+        let old_value = state_values[index];
+        visit_count[index] += 1;
+        let learning_rate = step_sizes.next(index, visit_count[index]);
+        state_values[index] += learning_rate * (target - state_values[index]);
+        (old_value - state_values[index]).powi(2)
+    };
+    // Discounted sum of the rewards on the window and discount of the step after it
+    let n_step_rewards = |window: &VecDeque<(S, f64)>| {
+        window
+            .iter()
+            .fold((0., 1.), |(n_step_rewards, discount), (_, reward)| {
+                (
+                    n_step_rewards + discount * reward,
+                    discount * discount_factor,
+                )
+            })
+    };
+
+    let mut progress = TrainingProgress::new(stopping_criterion);
+    let stopped_by = loop {
+        if let Some(stopped_by) = progress.stopping_criterion() {
+            break stopped_by;
+        }
+        let mut episode_variation = 0.;
+        let mut episode_return = 0.;
+        let mut episode_length = 0usize;
+
+        environment.reset_environment();
+        let mut window = VecDeque::with_capacity(steps);
+        let final_observation = loop {
+            let observation = match progress.episode_state(environment, agent, episode_length) {
+                EpisodeState::Ongoing(observation) => observation,
+                episode_state => break episode_state.truncated_on(),
+            };
+            let action = agent.act(&observation);
+            let reward = environment.receive_action(agent, &action);
+            episode_return += reward;
+            episode_length += 1;
+
+            if window.len() >= steps {
+                let (n_step_rewards, bootstrap_discount) = n_step_rewards(&window);
+                let (past_observation, _) = window
+                    .pop_front()
+                    .expect("There should be enough steps on the window.");
+                let target = n_step_rewards
+                    + bootstrap_discount * state_values[observation_space.index_of(&observation)];
+                episode_variation += update(&mut state_values, &past_observation, target);
+            }
+            window.push_back((observation, reward));
+        };
+        while !window.is_empty() {
+            let (n_step_rewards, bootstrap_discount) = n_step_rewards(&window);
+            let (past_observation, _) =
+                window.pop_front().expect("The window should not be empty.");
+            let target = n_step_rewards
+                + final_observation.map_or(0., |observation| {
+                    bootstrap_discount * state_values[observation_space.index_of(&observation)]
+                });
+            episode_variation += update(&mut state_values, &past_observation, target);
+        }
+
+        progress.end_episode(episode_return, episode_length, episode_variation);
+    };
+
+    PredictionReport {
+        state_values: VTable::from_storage(observation_space.clone(), state_values),
+        visit_counts: ObservationTable::from_storage(observation_space, visit_count),
+        episodes: progress.episodes(),
+        converged: stopped_by.is_convergence(),
+        stopped_by,
+    }
+}
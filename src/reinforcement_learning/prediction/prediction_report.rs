@@ -0,0 +1,37 @@
+use std::fmt::Display;
+
+use crate::{
+    reinforcement_learning::StoppingCriterion,
+    space::SpaceElement,
+    value_table::{Dense, ObservationTable, Storage, VTable},
+};
+
+// Docs imports
+#[allow(unused_imports)]
+use crate::reinforcement_learning::prediction::ValueEstimator;
+
+/// Result of a [`ValueEstimator::value_prediction`].
+pub struct PredictionReport<S, ST: Storage = Dense> {
+    /// Estimated state value of each observation.
+    pub state_values: VTable<S, ST>,
+    /// Number of times that each observation had its value updated.
+    pub visit_counts: ObservationTable<S, usize, ST>,
+    /// Number of episodes run.
+    pub episodes: usize,
+    /// Whether the prediction stopped because the state values converged.
+    pub converged: bool,
+    /// The [`StoppingCriterion`] that ended the prediction.
+    pub stopped_by: StoppingCriterion,
+}
+
+/// Writes the visit counts, the state values and the number of episodes.
+impl<S: SpaceElement, ST: Storage> Display for PredictionReport<S, ST> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Observation Visit Count")?;
+        write!(f, "{}", self.visit_counts)?;
+        writeln!(f, "State Value Function")?;
+        write!(f, "{}", self.state_values)?;
+        writeln!(f, "Iterated for {} episodes.", self.episodes)?;
+        writeln!(f, "Stopped by {:?}.", self.stopped_by)
+    }
+}
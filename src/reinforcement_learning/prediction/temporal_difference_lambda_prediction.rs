@@ -0,0 +1,163 @@
+use std::marker::PhantomData;
+
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, EpisodicEnvironment},
+    reinforcement_learning::{
+        prediction::{PredictionReport, ValueEstimator},
+        stopping_criterion::{EpisodeState, TrainingProgress},
+        temporal_difference::TraceType,
+        StoppingCriterion,
+    },
+    schedule::{StepSize, StepSizes},
+    space::SpaceElement,
+    value_table::{Dense, ObservationTable, Storage, StorageTable, VTable, ValueStorage},
+};
+
+/// TD(λ), predicts state values with eligibility traces, updating all recently
+/// visited observations after every step.
+pub struct TemporalDifferenceLambdaPrediction<E: EpisodicEnvironment, ST: Storage = Dense> {
+    lambda: f64,
+    step_size: StepSize,
+    discount_factor: f64,
+    trace_type: TraceType,
+    stopping_criterion: StoppingCriterion,
+    phantom_environment: PhantomData<(E, ST)>,
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > TemporalDifferenceLambdaPrediction<E, ST>
+{
+    /// # Panics
+    /// Panics if `lambda` is not in `[0, 1]`.
+    pub fn new(
+        episode_limit: usize,
+        lambda: f64,
+        alpha: f64,
+        discount_factor: f64,
+        trace_type: TraceType,
+    ) -> Self {
+        assert!((0. ..=1.).contains(&lambda), "Lambda must be in [0, 1].");
+        Self {
+            lambda,
+            step_size: StepSize::Constant(alpha),
+            discount_factor,
+            trace_type,
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episode_limit),
+            phantom_environment: PhantomData,
+        }
+    }
+
+    /// Replaces the [`StepSize`] of the updates, which is constant by default. The step
+    /// size of the visited observation is used for all the observations updated on that step.
+    #[must_use]
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = step_size;
+        self
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > ValueEstimator for TemporalDifferenceLambdaPrediction<E, ST>
+{
+    type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn value_prediction(self, environment: &mut E, agent: &AG) -> PredictionReport<S, ST> {
+        let observation_space = environment.observation_space();
+        let mut visit_count =
+            StorageTable::<ST, usize>::with_default(observation_space.len(), 1, 0);
+        let mut step_sizes = StepSizes::<ST>::new(self.step_size, observation_space.len(), 1);
+        let mut state_values =
+            StorageTable::<ST, f64>::with_default(observation_space.len(), 1, 0.);
+        let mut traces = StorageTable::<ST, f64>::with_default(observation_space.len(), 1, 0.);
+        // Observations visited on the episode, the only ones with non zero traces
+        let mut traced = StorageTable::<ST, bool>::with_default(observation_space.len(), 1, false);
+        let mut traced_indices = vec![];
+
+        let mut progress = TrainingProgress::new(&self.stopping_criterion);
+        let stopped_by = loop {
+            if let Some(stopped_by) = progress.stopping_criterion() {
+                break stopped_by;
+            }
+            let mut episode_variation = 0.;
+            let mut episode_return = 0.;
+            let mut episode_length = 0usize;
+
+            environment.reset_environment();
+            for index in traced_indices.drain(..) {
+                traces[index] = 0.;
+                traced[index] = false;
+            }
+
+            let mut current_observation = match progress.episode_state(environment, agent, 0) {
+                EpisodeState::Ongoing(observation) => Some(observation),
+                _ => None,
+            };
+            while let Some(observation) = current_observation {
+                let action = agent.act(&observation);
+                let reward = environment.receive_action(agent, &action);
+                episode_return += reward;
+                episode_length += 1;
+
+                let next_state = progress.episode_state(environment, agent, episode_length);
+                let index = observation_space.index_of(&observation);
+                visit_count[index] += 1;
+                let learning_rate = step_sizes.next(index, visit_count[index]);
+                let next_value = match &next_state {
+                    EpisodeState::Ongoing(next_observation)
+                    | EpisodeState::Truncated(next_observation) => {
+                        state_values[observation_space.index_of(next_observation)]
+                    }
+                    EpisodeState::Terminated => 0.,
+                };
+                let td_error = reward + self.discount_factor * next_value - state_values[index];
+
+                if !traced[index] {
+                    traced[index] = true;
+                    traced_indices.push(index);
+                }
+                traces[index] = match self.trace_type {
+                    TraceType::Accumulating => traces[index] + 1.,
+                    TraceType::Replacing => 1.,
+                    TraceType::Dutch => (1. - learning_rate) * traces[index] + 1.,
+                };
+                for traced_index in &traced_indices {
+                    let change = learning_rate * td_error * traces[*traced_index];
+                    state_values[*traced_index] += change;
+                    episode_variation += change.powi(2);
+                    traces[*traced_index] *= self.discount_factor * self.lambda;
+                }
+
+                current_observation = match next_state {
+                    EpisodeState::Ongoing(next_observation) => Some(next_observation),
+                    _ => None,
+                };
+            }
+
+            progress.end_episode(episode_return, episode_length, episode_variation);
+        };
+
+        PredictionReport {
+            state_values: VTable::from_storage(observation_space.clone(), state_values),
+            visit_counts: ObservationTable::from_storage(observation_space, visit_count),
+            episodes: progress.episodes(),
+            converged: stopped_by.is_convergence(),
+            stopped_by,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::marker::PhantomData;
+
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, EpisodicEnvironment},
+    reinforcement_learning::{
+        prediction::{n_step_temporal_difference_prediction, PredictionReport, ValueEstimator},
+        StoppingCriterion,
+    },
+    schedule::StepSize,
+    space::SpaceElement,
+    value_table::{Dense, Storage},
+};
+
+/// TD(0), predicts state values updating them towards `r + γ V(s')` after every step.
+pub struct TemporalDifferencePrediction<E: EpisodicEnvironment, ST: Storage = Dense> {
+    step_size: StepSize,
+    discount_factor: f64,
+    stopping_criterion: StoppingCriterion,
+    phantom_environment: PhantomData<(E, ST)>,
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > TemporalDifferencePrediction<E, ST>
+{
+    pub fn new(episode_limit: usize, alpha: f64, discount_factor: f64) -> Self {
+        Self {
+            step_size: StepSize::Constant(alpha),
+            discount_factor,
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episode_limit),
+            phantom_environment: PhantomData,
+        }
+    }
+
+    /// Replaces the [`StepSize`] of the updates, which is constant by default.
+    #[must_use]
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = step_size;
+        self
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > ValueEstimator for TemporalDifferencePrediction<E, ST>
+{
+    type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn value_prediction(self, environment: &mut E, agent: &AG) -> PredictionReport<S, ST> {
+        n_step_temporal_difference_prediction(
+            environment,
+            agent,
+            (1, self.step_size, self.discount_factor),
+            &self.stopping_criterion,
+        )
+    }
+}
@@ -0,0 +1,259 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    agent::Agent,
+    environment::{EpisodeEnd, EpisodicEnvironment},
+};
+
+// Docs imports
+#[allow(unused_imports)]
+use crate::reinforcement_learning::PolicyEstimator;
+
+/// Condition that ends a [`PolicyEstimator::policy_search`].
+///
+/// Criteria are checked before the start of every episode, so a search never
+/// stops in the middle of an episode. [`StoppingCriterion::MaxSteps`] and
+/// [`StoppingCriterion::WallClock`] are also checked before every step, and truncate
+/// the episode once met, so an episode that never ends can't overrun them.
+///
+/// Continuing tasks have no episodes, so their criteria are checked before every
+/// step, and the windows are measured in steps.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoppingCriterion {
+    /// Stops after the given number of episodes. Not available on continuing tasks.
+    MaxEpisodes(usize),
+    /// Stops after the [`Environment`](crate::environment::Environment) received
+    /// at least the given number of actions.
+    MaxSteps(usize),
+    /// Stops after the search has been running for at least the given time.
+    WallClock(Duration),
+    /// Stops when the sum of the squared changes to values of each of the last
+    /// `window` episodes is at most `tolerance`. Never met if `window` is zero.
+    ValueChangeTolerance { tolerance: f64, window: usize },
+    /// Stops when the average undiscounted return of the last `window` episodes
+    /// is at least `target`, or the average reward of the last `window` steps on
+    /// continuing tasks. Never met if `window` is zero.
+    TargetAverageReturn { target: f64, window: usize },
+    /// Stops when any of the criteria is met.
+    Any(Vec<StoppingCriterion>),
+    /// Stops when all of the criteria are met.
+    All(Vec<StoppingCriterion>),
+}
+
+impl StoppingCriterion {
+    /// Criterion used by the estimators when none is given, stops after `episodes` episodes
+    /// or when the values change less than [`f64::EPSILON`] for 5 episodes in a row.
+    #[must_use]
+    pub fn episode_limit_or_convergence(episodes: usize) -> Self {
+        Self::Any(vec![
+            Self::MaxEpisodes(episodes),
+            Self::ValueChangeTolerance {
+                tolerance: f64::EPSILON,
+                window: 5,
+            },
+        ])
+    }
+
+    /// Whether the criterion detects that the values have converged.
+    #[must_use]
+    pub fn is_convergence(&self) -> bool {
+        match self {
+            Self::ValueChangeTolerance {
+                tolerance: _,
+                window: _,
+            } => true,
+            Self::Any(criteria) | Self::All(criteria) => criteria.iter().any(Self::is_convergence),
+            _ => false,
+        }
+    }
+
+    /// Whether the criterion counts episodes, which continuing tasks don't have.
+    pub(crate) fn counts_episodes(&self) -> bool {
+        match self {
+            Self::MaxEpisodes(_) => true,
+            Self::Any(criteria) | Self::All(criteria) => criteria.iter().any(Self::counts_episodes),
+            _ => false,
+        }
+    }
+
+    /// Largest number of past episodes that the criterion needs to look at.
+    fn window(&self) -> usize {
+        match self {
+            Self::ValueChangeTolerance {
+                tolerance: _,
+                window,
+            }
+            | Self::TargetAverageReturn { target: _, window } => *window,
+            Self::Any(criteria) | Self::All(criteria) => {
+                criteria.iter().map(Self::window).max().unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Whether the step or time budget of the criterion runs out during an episode,
+    /// after `episode_length` steps that `progress` hasn't counted yet.
+    fn budget_spent(&self, progress: &TrainingProgress, episode_length: usize) -> bool {
+        match self {
+            Self::MaxSteps(steps) => progress.steps + episode_length >= *steps,
+            Self::WallClock(budget) => progress.start.elapsed() >= *budget,
+            Self::Any(criteria) => criteria
+                .iter()
+                .any(|criterion| criterion.budget_spent(progress, episode_length)),
+            Self::All(criteria) => criteria
+                .iter()
+                .all(|criterion| criterion.budget_spent(progress, episode_length)),
+            _ => false,
+        }
+    }
+
+    /// Returns the criterion that was met, if any.
+    ///
+    /// For [`StoppingCriterion::Any`] the first child that was met is returned, while
+    /// [`StoppingCriterion::All`] returns itself.
+    fn check(&self, progress: &TrainingProgress) -> Option<&Self> {
+        let met = match self {
+            Self::MaxEpisodes(episodes) => progress.episodes >= *episodes,
+            Self::MaxSteps(steps) => progress.steps >= *steps,
+            Self::WallClock(budget) => progress.start.elapsed() >= *budget,
+            Self::ValueChangeTolerance { tolerance, window } => {
+                *window > 0
+                    && progress.variations.len() >= *window
+                    && progress
+                        .variations
+                        .iter()
+                        .rev()
+                        .take(*window)
+                        .all(|variation| variation <= tolerance)
+            }
+            Self::TargetAverageReturn { target, window } => {
+                *window > 0
+                    && progress.returns.len() >= *window
+                    && progress.returns.iter().rev().take(*window).sum::<f64>() / *window as f64
+                        >= *target
+            }
+            Self::Any(criteria) => {
+                return criteria
+                    .iter()
+                    .find_map(|criterion| criterion.check(progress))
+            }
+            Self::All(criteria) => criteria
+                .iter()
+                .all(|criterion| criterion.check(progress).is_some()),
+        };
+        met.then_some(self)
+    }
+}
+
+/// State of an episode before each step.
+pub(crate) enum EpisodeState<S> {
+    /// The agent acts on the observation.
+    Ongoing(S),
+    Terminated,
+    /// The episode was cut short on the observation, whose value can be bootstrapped.
+    Truncated(S),
+}
+
+impl<S> EpisodeState<S> {
+    /// Observation to bootstrap from once the episode is over, if it was truncated.
+    pub fn truncated_on(self) -> Option<S> {
+        match self {
+            Self::Truncated(observation) => Some(observation),
+            Self::Ongoing(_) | Self::Terminated => None,
+        }
+    }
+}
+
+/// Tracks the progress of a search to evaluate a [`StoppingCriterion`].
+pub(crate) struct TrainingProgress<'a> {
+    criterion: &'a StoppingCriterion,
+    window: usize,
+    episodes: usize,
+    steps: usize,
+    start: Instant,
+    variations: VecDeque<f64>,
+    returns: VecDeque<f64>,
+}
+
+impl<'a> TrainingProgress<'a> {
+    pub fn new(criterion: &'a StoppingCriterion) -> Self {
+        let window = criterion.window();
+        Self {
+            criterion,
+            window,
+            episodes: 0,
+            steps: 0,
+            start: Instant::now(),
+            variations: VecDeque::with_capacity(window),
+            returns: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Number of episodes that have ended.
+    pub fn episodes(&self) -> usize {
+        self.episodes
+    }
+
+    /// Number of actions received by the environment.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Gets the next observation of the episode that `environment` runs, which is
+    /// truncated if the step or time budget of the criterion runs out after its
+    /// first `episode_length` steps.
+    pub fn episode_state<E: EpisodicEnvironment>(
+        &self,
+        environment: &mut E,
+        agent: &E::Agent,
+        episode_length: usize,
+    ) -> EpisodeState<<E::Agent as Agent>::Observation> {
+        match environment.get_observation(agent) {
+            Some(observation) if self.criterion.budget_spent(self, episode_length) => {
+                EpisodeState::Truncated(observation)
+            }
+            Some(observation) => EpisodeState::Ongoing(observation),
+            None => match environment.episode_end() {
+                EpisodeEnd::Terminated => EpisodeState::Terminated,
+                EpisodeEnd::Truncated => {
+                    EpisodeState::Truncated(environment.final_observation(agent))
+                }
+            },
+        }
+    }
+
+    /// Returns the criterion that was met, if the search should stop.
+    pub fn stopping_criterion(&self) -> Option<StoppingCriterion> {
+        self.criterion.check(self).cloned()
+    }
+
+    /// Episodes without steps can't change any value, so they are counted but left
+    /// out of the windows of the criteria.
+    pub fn end_episode(&mut self, episode_return: f64, episode_length: usize, variation: f64) {
+        self.episodes += 1;
+        self.steps += episode_length;
+        if episode_length > 0 {
+            self.push_window(episode_return, variation);
+        }
+    }
+
+    /// Ends a step of a continuing task, which fills the windows like an episode would.
+    pub fn end_step(&mut self, reward: f64, variation: f64) {
+        self.steps += 1;
+        self.push_window(reward, variation);
+    }
+
+    fn push_window(&mut self, episode_return: f64, variation: f64) {
+        if self.window > 0 {
+            if self.variations.len() == self.window {
+                self.variations.pop_front();
+                self.returns.pop_front();
+            }
+            self.variations.push_back(variation);
+            self.returns.push_back(episode_return);
+        }
+    }
+}
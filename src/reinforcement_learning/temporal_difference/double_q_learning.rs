@@ -0,0 +1,131 @@
+use std::marker::PhantomData;
+
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, Environment, EpisodicEnvironment},
+    random_number_generator::RandomNumberGeneratorFacade,
+    reinforcement_learning::{
+        temporal_difference::{
+            double_temporal_difference::DoubleTemporalDifference, TemporalDifferenceConfiguration,
+        },
+        PolicyEstimator, StoppingCriterion, TrainingObserver, TrainingReport,
+    },
+    schedule::StepSize,
+    space::{ActionMask, SpaceElement},
+    value_table::{Dense, QTable, Storage},
+};
+
+/// Q-Learning without maximization bias, the greedy action of one table is evaluated
+/// with the other table.
+pub struct DoubleQLearning<
+    E: EpisodicEnvironment,
+    RNG: RandomNumberGeneratorFacade,
+    ST: Storage = Dense,
+> {
+    stopping_criterion: StoppingCriterion,
+    step_size: StepSize,
+    discount_factor: f64,
+    random_number_generator: RNG,
+    phantom_env: PhantomData<(E, ST)>,
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > DoubleQLearning<E, RNG, ST>
+{
+    /// # Arguments
+    /// `random_number_generator`: Chooses which of the tables is updated on each step
+    pub fn new(
+        episode_limit: usize,
+        alpha: f64,
+        discount_factor: f64,
+        random_number_generator: RNG,
+    ) -> Self {
+        Self {
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episode_limit),
+            step_size: StepSize::Constant(alpha),
+            discount_factor,
+            random_number_generator,
+            phantom_env: PhantomData,
+        }
+    }
+
+    /// Replaces the [`StepSize`] of the updates, which is constant by default. Each
+    /// table counts its own updates of a pair.
+    #[must_use]
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = step_size;
+        self
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > PolicyEstimator for DoubleQLearning<E, RNG, ST>
+{
+    type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn policy_search_with_observer(
+        self,
+        environment: &mut Self::Environment,
+        agent: &mut <Self::Environment as Environment>::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
+        self.double_temporal_difference_policy_search(
+            environment,
+            agent,
+            observer,
+            &TemporalDifferenceConfiguration {
+                stopping_criterion: self.stopping_criterion.clone(),
+                temporal_difference_step: 1,
+                step_size: self.step_size,
+                discount_factor: self.discount_factor,
+            },
+            &self.random_number_generator,
+        )
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        RNG: RandomNumberGeneratorFacade,
+        ST: Storage,
+    > DoubleTemporalDifference<AC, S, AG, E> for DoubleQLearning<E, RNG, ST>
+{
+    fn double_evaluation(
+        &self,
+        _agent: &AG,
+        selection_value: &QTable<S, AC, ST>,
+        evaluation_value: &QTable<S, AC, ST>,
+        action_mask: &ActionMask<S, AC>,
+        next_step: Option<(&S, &AC)>,
+    ) -> f64 {
+        match next_step {
+            Some((next_state, _next_action)) => {
+                evaluation_value[(
+                    next_state,
+                    &selection_value.legal_greedy_action(next_state, action_mask),
+                )]
+            }
+            None => 0.,
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, Environment, EpisodicEnvironment},
+    random_number_generator::RandomNumberGeneratorFacade,
+    reinforcement_learning::{
+        stopping_criterion::{EpisodeState, TrainingProgress},
+        temporal_difference::{final_step, TemporalDifferenceConfiguration},
+        PolicyEstimator, TrainingObserver, TrainingReport,
+    },
+    schedule::StepSizes,
+    space::{ActionMask, SpaceElement},
+    value_table::{ObservationActionTable, QTable, ValueStorage},
+};
+
+/// Temporal Difference with two independent estimates of the action values, which
+/// avoids the maximization bias of bootstrapping on the estimate being updated.
+///
+/// On each step one of the tables is randomly chosen to be updated, bootstrapping on
+/// the other one. The agent is fed the average of both tables.
+pub(super) trait DoubleTemporalDifference<
+    AC: SpaceElement,
+    S: SpaceElement,
+    AG: Agent<Action = AC, Observation = S>,
+    E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+>: PolicyEstimator<Environment = E>
+{
+    /// Value of `next_step` for the table being updated.
+    ///
+    /// # Arguments
+    /// `selection_value`: The table being updated, used to select actions</br>
+    /// `evaluation_value`: The other table, used to evaluate the selected actions</br>
+    /// `action_mask`: Legal actions that can be selected or averaged over
+    fn double_evaluation(
+        &self,
+        agent: &AG,
+        selection_value: &QTable<S, AC, Self::Storage>,
+        evaluation_value: &QTable<S, AC, Self::Storage>,
+        action_mask: &ActionMask<S, AC>,
+        next_step: Option<(&S, &AC)>,
+    ) -> f64;
+
+    /// Updates the value of the pair `(s, a)` on one of the tables towards
+    /// `r + γ Q(s', a')`, where `Q(s', a')` is given by [`DoubleTemporalDifference::double_evaluation`].
+    ///
+    /// # Return
+    /// Change to the average value squared
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    fn double_temporal_difference_policy_evaluation(
+        &self,
+        agent: &mut AG,
+        observer: &mut dyn TrainingObserver<S, AC>,
+        (first_action_value, second_action_value): (
+            &mut QTable<S, AC, Self::Storage>,
+            &mut QTable<S, AC, Self::Storage>,
+        ),
+        (visit_counts, step_sizes): (
+            &mut [ObservationActionTable<S, AC, usize, Self::Storage>; 2],
+            &mut [StepSizes<Self::Storage>; 2],
+        ),
+        action_mask: &ActionMask<S, AC>,
+        (s, a, r, next_step): (&S, &AC, f64, Option<(&S, &AC)>),
+        temporal_difference_configuration: &TemporalDifferenceConfiguration,
+        random_number_generator: &impl RandomNumberGeneratorFacade,
+    ) -> f64 {
+        let prev_index = first_action_value.tabular_index(s, a);
+        let old_value = (first_action_value[prev_index] + second_action_value[prev_index]) / 2.;
+
+        let (selection, selection_value, evaluation_value) =
+            if random_number_generator.random() < 0.5 {
+                (0, first_action_value, &*second_action_value)
+            } else {
+                (1, second_action_value, &*first_action_value)
+            };
+        // Each table is only updated half of the time, so it counts its own updates
+        visit_counts[selection][prev_index] += 1;
+        let learning_rate =
+            step_sizes[selection].next(prev_index, visit_counts[selection][prev_index]);
+        let double_evaluation = self.double_evaluation(
+            agent,
+            selection_value,
+            evaluation_value,
+            action_mask,
+            next_step,
+        );
+
+        // Update state-action value
+        selection_value[prev_index] = selection_value[prev_index]
+            + learning_rate
+                * (r + temporal_difference_configuration.discount_factor * double_evaluation
+                    - selection_value[prev_index]);
+        let new_value = (selection_value[prev_index] + evaluation_value[prev_index]) / 2.;
+        observer.on_value_update(s, a, old_value, new_value);
+        // Propagate change to policy
+        let average_values: Vec<f64> = selection_value
+            .observation_values(s)
+            .iter()
+            .zip(evaluation_value.observation_values(s))
+            .map(|(selection, evaluation)| (selection + evaluation) / 2.)
+            .collect();
+        let visits: Vec<usize> = visit_counts[0]
+            .observation_values(s)
+            .iter()
+            .zip(visit_counts[1].observation_values(s))
+            .map(|(first, second)| first + second)
+            .collect();
+        agent.policy_improvement_with_visits(s, &average_values, &visits);
+
+        (old_value - new_value).powi(2)
+    }
+
+    fn double_temporal_difference_policy_search(
+        &self,
+        environment: &mut Self::Environment,
+        agent: &mut <Self::Environment as Environment>::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+        temporal_difference_configuration: &TemporalDifferenceConfiguration,
+        random_number_generator: &impl RandomNumberGeneratorFacade,
+    ) -> TrainingReport<S, AC, Self::Storage> {
+        let observation_space = environment.observation_space();
+        let action_space = environment.action_space();
+        let action_mask = environment.action_mask();
+        let mut first_action_value = QTable::<S, AC, Self::Storage>::with_spaces(
+            observation_space.clone(),
+            action_space.clone(),
+            0.,
+        );
+        let mut second_action_value = first_action_value.clone();
+        let mut visit_count = ObservationActionTable::<S, AC, usize, Self::Storage>::with_spaces(
+            observation_space.clone(),
+            action_space.clone(),
+            0,
+        );
+        let mut visit_counts = [visit_count.clone(), visit_count.clone()];
+        let mut step_sizes = [(); 2].map(|()| {
+            StepSizes::new(
+                temporal_difference_configuration.step_size,
+                observation_space.len(),
+                action_space.len(),
+            )
+        });
+
+        let mut progress =
+            TrainingProgress::new(&temporal_difference_configuration.stopping_criterion);
+        let stopped_by = loop {
+            if let Some(stopped_by) = progress.stopping_criterion() {
+                break stopped_by;
+            }
+            let mut episode_variation = 0.;
+            let mut episode_return = 0.;
+            let mut episode_length = 0usize;
+
+            environment.reset_environment();
+            observer.on_episode_start(progress.episodes() + 1);
+
+            let mut previous_step = None;
+            let truncated_on = loop {
+                let observation = match progress.episode_state(environment, agent, episode_length) {
+                    EpisodeState::Ongoing(observation) => observation,
+                    episode_state => break episode_state.truncated_on(),
+                };
+                let action = agent.act(&observation);
+                let reward = environment.receive_action(agent, &action);
+                observer.on_step(&observation, &action, reward);
+                agent.on_step_end();
+                episode_return += reward;
+                episode_length += 1;
+
+                if let Some((past_obs, past_action, past_reward)) = previous_step.take() {
+                    episode_variation += self.double_temporal_difference_policy_evaluation(
+                        agent,
+                        observer,
+                        (&mut first_action_value, &mut second_action_value),
+                        (&mut visit_counts, &mut step_sizes),
+                        &action_mask,
+                        (
+                            &past_obs,
+                            &past_action,
+                            past_reward,
+                            Some((&observation, &action)),
+                        ),
+                        temporal_difference_configuration,
+                        random_number_generator,
+                    );
+                }
+                previous_step = Some((observation, action, reward));
+            };
+
+            if let Some((past_obs, past_action, past_reward)) = previous_step {
+                // Double evaluations select their own action on the final observation
+                let final_step = final_step(truncated_on, &first_action_value, &action_mask);
+                episode_variation += self.double_temporal_difference_policy_evaluation(
+                    agent,
+                    observer,
+                    (&mut first_action_value, &mut second_action_value),
+                    (&mut visit_counts, &mut step_sizes),
+                    &action_mask,
+                    (
+                        &past_obs,
+                        &past_action,
+                        past_reward,
+                        final_step
+                            .as_ref()
+                            .map(|(final_observation, final_action)| {
+                                (final_observation, final_action)
+                            }),
+                    ),
+                    temporal_difference_configuration,
+                    random_number_generator,
+                );
+            }
+
+            observer.on_episode_end(episode_return, episode_length, episode_variation);
+
+            agent.on_episode_end();
+            progress.end_episode(episode_return, episode_length, episode_variation);
+        };
+
+        // Every row updated on either table was visited
+        let mut action_value =
+            QTable::<S, AC, Self::Storage>::with_spaces(observation_space, action_space, 0.);
+        let [first_visit_count, second_visit_count] = &visit_counts;
+        let visited_rows = first_visit_count
+            .storage()
+            .stored_rows()
+            .chain(second_visit_count.storage().stored_rows())
+            .map(|(row, _)| row);
+        for row in visited_rows {
+            let observation = action_value.observation_space().get(row);
+            for (value, (first, second)) in action_value
+                .observation_values_mut(&observation)
+                .iter_mut()
+                .zip(
+                    first_action_value
+                        .observation_values(&observation)
+                        .iter()
+                        .zip(second_action_value.observation_values(&observation)),
+                )
+            {
+                *value = (first + second) / 2.;
+            }
+            for (visits, (first, second)) in visit_count
+                .observation_values_mut(&observation)
+                .iter_mut()
+                .zip(
+                    first_visit_count
+                        .observation_values(&observation)
+                        .iter()
+                        .zip(second_visit_count.observation_values(&observation)),
+                )
+            {
+                *visits = first + second;
+            }
+        }
+
+        TrainingReport {
+            action_values: action_value,
+            visit_counts: visit_count,
+            episodes: progress.episodes(),
+            steps: progress.steps(),
+            converged: stopped_by.is_convergence(),
+            stopped_by,
+            average_reward: None,
+        }
+    }
+}
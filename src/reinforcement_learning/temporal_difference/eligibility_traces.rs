@@ -0,0 +1,298 @@
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, Environment, EpisodicEnvironment},
+    reinforcement_learning::{
+        stopping_criterion::{EpisodeState, TrainingProgress},
+        temporal_difference::final_step,
+        PolicyEstimator, StoppingCriterion, TrainingObserver, TrainingReport,
+    },
+    schedule::{StepSize, StepSizes},
+    space::{ActionMask, DiscreteSpace, SpaceElement},
+    value_table::{ObservationActionTable, QTable, Storage},
+};
+
+/// How the eligibility trace of the visited observation-action pair is increased.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceType {
+    /// `e(s, a) = e(s, a) + 1`
+    Accumulating,
+    /// `e(s, a) = 1`
+    Replacing,
+    /// `e(s, a) = (1 - α) e(s, a) + 1`
+    Dutch,
+}
+
+pub(super) struct EligibilityTraceConfiguration {
+    pub stopping_criterion: StoppingCriterion,
+    pub step_size: StepSize,
+    pub discount_factor: f64,
+    pub lambda: f64,
+    pub trace_type: TraceType,
+}
+
+/// Values and traces of a search with eligibility traces.
+///
+/// Only the pairs visited during the current episode can have non zero traces, so
+/// those are kept on a list to avoid sweeping the whole table on every step.
+pub(super) struct EligibilityTraceState<S, AC, ST: Storage> {
+    action_value: QTable<S, AC, ST>,
+    visit_count: ObservationActionTable<S, AC, usize, ST>,
+    step_sizes: StepSizes<ST>,
+    traces: QTable<S, AC, ST>,
+    traced: ObservationActionTable<S, AC, bool, ST>,
+    traced_indices: Vec<usize>,
+    action_mask: ActionMask<S, AC>,
+    /// Value of the current pair estimated on the previous step, used by True Online TD(λ)
+    pub previous_value: f64,
+}
+
+impl<S: SpaceElement, AC: SpaceElement, ST: Storage> EligibilityTraceState<S, AC, ST> {
+    fn new(
+        observation_space: DiscreteSpace<S>,
+        action_space: DiscreteSpace<AC>,
+        action_mask: ActionMask<S, AC>,
+        step_size: StepSize,
+    ) -> Self {
+        Self {
+            step_sizes: StepSizes::new(step_size, observation_space.len(), action_space.len()),
+            action_value: QTable::with_spaces(observation_space.clone(), action_space.clone(), 0.),
+            visit_count: ObservationActionTable::with_spaces(
+                observation_space.clone(),
+                action_space.clone(),
+                0,
+            ),
+            traces: QTable::with_spaces(observation_space.clone(), action_space.clone(), 0.),
+            traced: ObservationActionTable::with_spaces(observation_space, action_space, false),
+            traced_indices: vec![],
+            action_mask,
+            previous_value: 0.,
+        }
+    }
+
+    pub fn action_value(&self) -> &QTable<S, AC, ST> {
+        &self.action_value
+    }
+
+    /// Legal actions of the [`DiscreteEnvironment`] being searched.
+    pub fn action_mask(&self) -> &ActionMask<S, AC> {
+        &self.action_mask
+    }
+
+    pub fn value(&self, index: usize) -> f64 {
+        self.action_value[index]
+    }
+
+    pub fn visit(&mut self, index: usize) {
+        self.visit_count[index] += 1;
+    }
+
+    /// Step size of the updates of the step that visited `index`, which must be
+    /// called once per visit.
+    pub fn step_size(&mut self, index: usize) -> f64 {
+        self.step_sizes.next(index, self.visit_count[index])
+    }
+
+    /// Increases the trace of `index` according to `trace_type`.
+    pub fn increase_trace(&mut self, index: usize, trace_type: TraceType, learning_rate: f64) {
+        if !self.traced[index] {
+            self.traced[index] = true;
+            self.traced_indices.push(index);
+        }
+        self.traces[index] = match trace_type {
+            TraceType::Accumulating => self.traces[index] + 1.,
+            TraceType::Replacing => 1.,
+            TraceType::Dutch => (1. - learning_rate) * self.traces[index] + 1.,
+        };
+    }
+
+    /// Multiplies all traces by `factor`.
+    pub fn decay_traces(&mut self, factor: f64) {
+        for index in &self.traced_indices {
+            self.traces[*index] *= factor;
+        }
+    }
+
+    /// Sets all traces to zero.
+    pub fn clear_traces(&mut self) {
+        for index in self.traced_indices.drain(..) {
+            self.traces[index] = 0.;
+            self.traced[index] = false;
+        }
+    }
+
+    /// Adds `increment(index, trace)` to the value of every pair with a trace, and
+    /// propagates the values of the changed observations to the `agent`.
+    ///
+    /// # Return
+    /// Sum of the changes to values squared
+    pub fn update_values<AG: Agent<Action = AC, Observation = S>>(
+        &mut self,
+        agent: &mut AG,
+        observer: &mut dyn TrainingObserver<S, AC>,
+        increment: impl Fn(usize, f64) -> f64,
+    ) -> f64 {
+        let mut variation = 0.;
+        let mut improved_observations = vec![];
+        for index in &self.traced_indices {
+            let change = increment(*index, self.traces[*index]);
+            if change != 0. {
+                let action_count = self.action_value.action_space().len();
+                let observation = self
+                    .action_value
+                    .observation_space()
+                    .get(index / action_count);
+                let action = self.action_value.action_space().get(index % action_count);
+                let old_value = self.action_value[*index];
+
+                self.action_value[*index] += change;
+                observer.on_value_update(
+                    &observation,
+                    &action,
+                    old_value,
+                    self.action_value[*index],
+                );
+                improved_observations.push(index / action_count);
+
+                variation += change.powi(2);
+            }
+        }
+        // Propagate changes to policy, once per observation
+        improved_observations.sort_unstable();
+        improved_observations.dedup();
+        for observation_index in improved_observations {
+            let observation = self.action_value.observation_space().get(observation_index);
+            agent.policy_improvement_with_visits(
+                &observation,
+                self.action_value.observation_values(&observation),
+                self.visit_count.observation_values(&observation),
+            );
+        }
+        variation
+    }
+}
+
+pub(super) trait EligibilityTraces<
+    AC: SpaceElement,
+    S: SpaceElement,
+    AG: Agent<Action = AC, Observation = S>,
+    E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+>: PolicyEstimator<Environment = E>
+{
+    /// Updates the values and traces with the `transition` `(s, a, r, next_step)`, where
+    /// `next_step` is `None` if the episode terminated.
+    ///
+    /// # Return
+    /// Sum of the changes to values squared
+    fn eligibility_trace_update(
+        &self,
+        agent: &mut AG,
+        observer: &mut dyn TrainingObserver<S, AC>,
+        eligibility_trace_state: &mut EligibilityTraceState<S, AC, Self::Storage>,
+        transition: (&S, &AC, f64, Option<(&S, &AC)>),
+        eligibility_trace_configuration: &EligibilityTraceConfiguration,
+    ) -> f64;
+
+    fn eligibility_trace_policy_search(
+        &self,
+        environment: &mut Self::Environment,
+        agent: &mut <Self::Environment as Environment>::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+        eligibility_trace_configuration: &EligibilityTraceConfiguration,
+    ) -> TrainingReport<S, AC, Self::Storage> {
+        let mut eligibility_trace_state = EligibilityTraceState::new(
+            environment.observation_space(),
+            environment.action_space(),
+            environment.action_mask(),
+            eligibility_trace_configuration.step_size,
+        );
+
+        let mut progress =
+            TrainingProgress::new(&eligibility_trace_configuration.stopping_criterion);
+        let stopped_by = loop {
+            if let Some(stopped_by) = progress.stopping_criterion() {
+                break stopped_by;
+            }
+            let mut episode_variation = 0.;
+            let mut episode_return = 0.;
+            let mut episode_length = 0usize;
+
+            environment.reset_environment();
+            eligibility_trace_state.clear_traces();
+            eligibility_trace_state.previous_value = 0.;
+            observer.on_episode_start(progress.episodes() + 1);
+
+            let mut current_step = match progress.episode_state(environment, agent, 0) {
+                EpisodeState::Ongoing(observation) => {
+                    let action = agent.act(&observation);
+                    Some((observation, action))
+                }
+                _ => None,
+            };
+            while let Some((observation, action)) = current_step {
+                let reward = environment.receive_action(agent, &action);
+                observer.on_step(&observation, &action, reward);
+                agent.on_step_end();
+                episode_return += reward;
+                episode_length += 1;
+
+                let (next_step, final_step) =
+                    match progress.episode_state(environment, agent, episode_length) {
+                        EpisodeState::Ongoing(next_observation) => {
+                            let next_action = agent.act(&next_observation);
+                            (Some((next_observation, next_action)), None)
+                        }
+                        episode_state => (
+                            None,
+                            final_step(
+                                episode_state.truncated_on(),
+                                &eligibility_trace_state.action_value,
+                                &eligibility_trace_state.action_mask,
+                            ),
+                        ),
+                    };
+                episode_variation += self.eligibility_trace_update(
+                    agent,
+                    observer,
+                    &mut eligibility_trace_state,
+                    (
+                        &observation,
+                        &action,
+                        reward,
+                        next_step
+                            .as_ref()
+                            .or(final_step.as_ref())
+                            .map(|(next_observation, next_action)| (next_observation, next_action)),
+                    ),
+                    eligibility_trace_configuration,
+                );
+                current_step = next_step;
+            }
+
+            observer.on_episode_end(episode_return, episode_length, episode_variation);
+
+            agent.on_episode_end();
+            progress.end_episode(episode_return, episode_length, episode_variation);
+        };
+
+        TrainingReport {
+            action_values: eligibility_trace_state.action_value,
+            visit_counts: eligibility_trace_state.visit_count,
+            episodes: progress.episodes(),
+            steps: progress.steps(),
+            converged: stopped_by.is_convergence(),
+            stopped_by,
+            average_reward: None,
+        }
+    }
+
+    /// Index of `(s, a)` after counting the visit.
+    fn visit(
+        eligibility_trace_state: &mut EligibilityTraceState<S, AC, Self::Storage>,
+        s: &S,
+        a: &AC,
+    ) -> usize {
+        let index = eligibility_trace_state.action_value.tabular_index(s, a);
+        eligibility_trace_state.visit(index);
+        index
+    }
+}
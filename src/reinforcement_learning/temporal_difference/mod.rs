@@ -1,119 +1,215 @@
+mod double_expected_sarsa;
+mod double_q_learning;
+mod double_temporal_difference;
+mod eligibility_traces;
 mod expected_sarsa;
+mod n_step_expected_sarsa;
+mod n_step_q_learning;
+mod n_step_sarsa;
 mod q_learning;
 mod sarsa;
+mod sarsa_lambda;
+mod true_online_sarsa_lambda;
+mod watkins_q_lambda;
 
 // Re-exports
-pub use self::{expected_sarsa::ExpectedSARSA, q_learning::QLearning, sarsa::SARSA};
+pub use self::{
+    double_expected_sarsa::DoubleExpectedSARSA, double_q_learning::DoubleQLearning,
+    eligibility_traces::TraceType, expected_sarsa::ExpectedSARSA,
+    n_step_expected_sarsa::NStepExpectedSARSA, n_step_q_learning::NStepQLearning,
+    n_step_sarsa::NStepSARSA, q_learning::QLearning, sarsa::SARSA, sarsa_lambda::SARSALambda,
+    true_online_sarsa_lambda::TrueOnlineSARSALambda, watkins_q_lambda::WatkinsQLambda,
+};
 
 use std::collections::VecDeque;
 
 use crate::{
-    action::DiscreteAction,
     agent::Agent,
-    environment::{Environment, EpisodicEnvironment},
-    observation::DiscreteObservation,
-    reinforcement_learning::PolicyEstimator,
+    environment::{DiscreteEnvironment, Environment, EpisodicEnvironment},
+    reinforcement_learning::{
+        stopping_criterion::{EpisodeState, TrainingProgress},
+        PolicyEstimator, StoppingCriterion, TrainingObserver, TrainingReport,
+    },
+    schedule::{StepSize, StepSizes},
+    space::{ActionMask, SpaceElement},
     trajectory::Trajectory,
+    value_table::{ObservationActionTable, QTable, Storage},
 };
 
 use super::DiscretePolicyEstimator;
 
 struct TemporalDifferenceConfiguration {
-    pub episode_limit: usize,
+    pub stopping_criterion: StoppingCriterion,
     pub temporal_difference_step: usize,
-    pub learning_rate: f64,
+    pub step_size: StepSize,
     pub discount_factor: f64,
 }
 
 trait TemporalDifference<
-    AC: DiscreteAction,
-    S: DiscreteObservation,
+    AC: SpaceElement,
+    S: SpaceElement,
     AG: Agent<Action = AC, Observation = S>,
-    E: EpisodicEnvironment<Agent = AG>,
+    E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
 >: PolicyEstimator<Environment = E>
 {
+    /// Value of `next_step` bootstrapped by the update, where `action_mask` gives the
+    /// legal actions that the estimators that maximize or average over actions
+    /// can consider.
     fn algorithm_specific_evaluation(
         &self,
         agent: &AG,
-        action_value: &mut [f64],
+        action_value: &QTable<S, AC, Self::Storage>,
+        action_mask: &ActionMask<S, AC>,
         next_step: Option<(&S, &AC)>,
     ) -> f64;
 
+    /// Updates the value of the pair `(s, a)` towards the `n`-step return
+    /// `G{t:t+n} = r{t+1} + γ r{t+2} + ... + γ^{n-1} r{t+n} + γ^n Q(s{t+n}, a{t+n})`
+    /// where `Q(s{t+n}, a{t+n})` is given by [`TemporalDifference::algorithm_specific_evaluation`].
+    ///
+    /// # Arguments
+    /// `n_step_rewards`: Discounted sum of the rewards, `r{t+1} + ... + γ^{n-1} r{t+n}`</br>
+    /// `bootstrap_discount`: Discount of the value of the next step, `γ^n`</br>
+    /// `next_step`: Observation and action `n` steps after `(s, a)`, or `None` if the episode terminated
+    /// # Return
+    /// Change to value squared
+    #[allow(clippy::too_many_arguments)]
     fn temporal_difference_policy_evaluation(
         &self,
         agent: &mut AG,
-        action_value: &mut [f64],
-        (s, a, r, next_step): (&S, &AC, f64, Option<(&S, &AC)>),
-        temporal_difference_configuration: &TemporalDifferenceConfiguration,
+        observer: &mut dyn TrainingObserver<S, AC>,
+        action_value: &mut QTable<S, AC, Self::Storage>,
+        visit_count: &mut ObservationActionTable<S, AC, usize, Self::Storage>,
+        step_sizes: &mut StepSizes<Self::Storage>,
+        action_mask: &ActionMask<S, AC>,
+        (s, a, n_step_rewards, next_step): (&S, &AC, f64, Option<(&S, &AC)>),
+        bootstrap_discount: f64,
     ) -> f64 {
-        let prev_index = Self::tabular_index(a, s);
+        let prev_index = visit_count.tabular_index(s, a);
+        visit_count[prev_index] += 1;
+        let learning_rate = step_sizes.next(prev_index, visit_count[prev_index]);
         let algorithm_specific_evaluation =
-            self.algorithm_specific_evaluation(agent, action_value, next_step);
+            self.algorithm_specific_evaluation(agent, action_value, action_mask, next_step);
 
         let old_value = action_value[prev_index];
 
         // Update state-action value
         action_value[prev_index] = action_value[prev_index]
-            + temporal_difference_configuration.learning_rate
-                * (r + temporal_difference_configuration.discount_factor
-                    * algorithm_specific_evaluation
+            + learning_rate
+                * (n_step_rewards + bootstrap_discount * algorithm_specific_evaluation
                     - action_value[prev_index]);
+        observer.on_value_update(s, a, old_value, action_value[prev_index]);
         // Propagate change to policy
-        AC::ACTIONS.iter().for_each(|action| {
-            let tabular_index = s.index() * AC::ACTIONS.len() + action.index();
-            agent.policy_improvemnt(action, s, action_value[tabular_index]);
-        });
+        Self::improve_policy(agent, s, action_value, visit_count);
 
         (old_value - action_value[prev_index]).powi(2)
     }
 
+    /// Discounted sum of the rewards on the sliding window and the discount of the
+    /// step that follows the window.
+    fn n_step_rewards(
+        temporal_difference: &VecDeque<Trajectory<S, AC>>,
+        discount_factor: f64,
+    ) -> (f64, f64) {
+        temporal_difference
+            .iter()
+            .fold((0., 1.), |(n_step_rewards, discount), step| match step {
+                Trajectory::Step {
+                    observation: _,
+                    action: _,
+                    reward,
+                } => (n_step_rewards + discount * reward, discount * discount_factor),
+                Trajectory::Final {
+                    observation: _,
+                    episode_end: _,
+                } => panic!(
+                    "A final state shouldn't have been added to the temporal difference sliding window."
+                ),
+            })
+    }
+
     fn temporal_difference_policy_search(
         &self,
         environment: &mut Self::Environment,
         agent: &mut <Self::Environment as Environment>::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
         temporal_difference_configuration: &TemporalDifferenceConfiguration,
-    ) {
-        let mut action_value = vec![0.; S::OBSERVATIONS.len() * AC::ACTIONS.len()];
+    ) -> TrainingReport<S, AC, Self::Storage> {
+        let observation_space = environment.observation_space();
+        let action_space = environment.action_space();
+        let action_mask = environment.action_mask();
+        let mut step_sizes = StepSizes::new(
+            temporal_difference_configuration.step_size,
+            observation_space.len(),
+            action_space.len(),
+        );
+        let mut action_value = QTable::<S, AC, Self::Storage>::with_spaces(
+            observation_space.clone(),
+            action_space.clone(),
+            0.,
+        );
+        let mut visit_count = ObservationActionTable::<S, AC, usize, Self::Storage>::with_spaces(
+            observation_space,
+            action_space,
+            0,
+        );
 
-        let mut episode = 0usize;
-        let mut episode_variation_window = VecDeque::from_iter([f64::MAX; 5]);
-        while episode_variation_window
-            .iter()
-            .any(|ep_v| ep_v > &f64::EPSILON)
-            && episode < temporal_difference_configuration.episode_limit
-        {
-            episode += 1;
+        let mut progress =
+            TrainingProgress::new(&temporal_difference_configuration.stopping_criterion);
+        let stopped_by = loop {
+            if let Some(stopped_by) = progress.stopping_criterion() {
+                break stopped_by;
+            }
             let mut episode_variation = 0.;
+            let mut episode_return = 0.;
+            let mut episode_length = 0usize;
 
             environment.reset_environment();
+            observer.on_episode_start(progress.episodes() + 1);
 
             let mut temporal_difference =
                 VecDeque::with_capacity(temporal_difference_configuration.temporal_difference_step);
-            while let Some(observation) = environment.get_observation(agent) {
+            let truncated_on = loop {
+                let observation = match progress.episode_state(environment, agent, episode_length) {
+                    EpisodeState::Ongoing(observation) => observation,
+                    episode_state => break episode_state.truncated_on(),
+                };
                 let action = agent.act(&observation);
                 let reward = environment.receive_action(agent, &action);
+                observer.on_step(&observation, &action, reward);
+                agent.on_step_end();
+                episode_return += reward;
+                episode_length += 1;
 
                 if temporal_difference.len()
                     >= temporal_difference_configuration.temporal_difference_step
                 {
+                    let (n_step_rewards, bootstrap_discount) = Self::n_step_rewards(
+                        &temporal_difference,
+                        temporal_difference_configuration.discount_factor,
+                    );
                     let past_step = temporal_difference.pop_front().expect("There should be enough Steps on the trajectory to calculate the Temporal Difference.");
 
                     match past_step {
                         Trajectory::Step {
                             observation: past_obs,
                             action: past_action,
-                            reward: past_reward,
+                            reward: _,
                         } => {
                             episode_variation += self.temporal_difference_policy_evaluation(
                                 agent,
+                                observer,
                                 &mut action_value,
+                                &mut visit_count,
+                                &mut step_sizes,
+                                &action_mask,
                                 (
                                     &past_obs,
                                     &past_action,
-                                    past_reward,
+                                    n_step_rewards,
                                     Some((&observation, &action)),
                                 ),
-                                temporal_difference_configuration,
+                                bootstrap_discount,
                             );
                         }
                         _ => panic!("A final state shouldn't be reached at this point"),
@@ -124,47 +220,73 @@ trait TemporalDifference<
                     action,
                     reward,
                 });
-            }
+            };
 
-            for remaining_step in temporal_difference.into_iter() {
-                match remaining_step {
-                    Trajectory::Step {
+            let final_step = final_step(truncated_on, &action_value, &action_mask);
+            while !temporal_difference.is_empty() {
+                let (n_step_rewards, bootstrap_discount) = Self::n_step_rewards(
+                    &temporal_difference,
+                    temporal_difference_configuration.discount_factor,
+                );
+                match temporal_difference.pop_front() {
+                    Some(Trajectory::Step {
                         observation,
                         action,
-                        reward,
-                    } => {
+                        reward: _,
+                    }) => {
                         episode_variation += self.temporal_difference_policy_evaluation(
                             agent,
+                            observer,
                             &mut action_value,
+                            &mut visit_count,
+                            &mut step_sizes,
+                            &action_mask,
                             (
                                 &observation,
                                 &action,
-                                reward,
-                                None
+                                n_step_rewards,
+                                final_step.as_ref().map(|(final_observation, final_action)| {
+                                    (final_observation, final_action)
+                                }),
                             ),
-                            temporal_difference_configuration
+                            bootstrap_discount,
                         );
                     }
                     _ => panic!("A final state shouldn't have been added to the temporal difference sliding window."),
                 }
             }
 
-            episode_variation_window.pop_front();
-            episode_variation_window.push_back(episode_variation);
-        }
+            observer.on_episode_end(episode_return, episode_length, episode_variation);
 
-        Self::print_observation_action_pairs("Action Value Function", &action_value);
-        println!("Iterated for {} episodes.", episode);
-    }
+            agent.on_episode_end();
+            progress.end_episode(episode_return, episode_length, episode_variation);
+        };
 
-    fn print_observation_action_pairs(header: &str, list: &[f64]) {
-        println!("{header}");
-        for (acts, s) in list.chunks(AC::ACTIONS.len()).zip(S::OBSERVATIONS) {
-            print!("{s:?} ");
-            for (action, value) in AC::ACTIONS.iter().zip(acts) {
-                print!("[{action:?}; {value}] ");
-            }
-            println!();
+        TrainingReport {
+            action_values: action_value,
+            visit_counts: visit_count,
+            episodes: progress.episodes(),
+            steps: progress.steps(),
+            converged: stopped_by.is_convergence(),
+            stopped_by,
+            average_reward: None,
         }
     }
 }
+
+/// Observation the episode was truncated on, if any, so the last steps bootstrap from
+/// its value instead of assuming zero, with its greedy legal action on `action_value`.
+///
+/// Sampling the action of the agent would add variance to the bootstrapped value and draw
+/// from its random number generator. Estimators that average over the policy of the
+/// agent, like Expected SARSA, only use the observation.
+fn final_step<AC: SpaceElement, S: SpaceElement, ST: Storage>(
+    truncated_on: Option<S>,
+    action_value: &QTable<S, AC, ST>,
+    action_mask: &ActionMask<S, AC>,
+) -> Option<(S, AC)> {
+    truncated_on.map(|final_observation| {
+        let final_action = action_value.legal_greedy_action(&final_observation, action_mask);
+        (final_observation, final_action)
+    })
+}
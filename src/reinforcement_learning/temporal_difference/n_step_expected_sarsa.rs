@@ -0,0 +1,117 @@
+use std::marker::PhantomData;
+
+use crate::{
+    agent::{Agent, DiscreteAgent},
+    environment::{DiscreteEnvironment, Environment, EpisodicEnvironment},
+    reinforcement_learning::{
+        temporal_difference::{TemporalDifference, TemporalDifferenceConfiguration},
+        PolicyEstimator, StoppingCriterion, TrainingObserver, TrainingReport,
+    },
+    schedule::StepSize,
+    space::{ActionMask, SpaceElement},
+    value_table::{Dense, QTable, Storage},
+};
+
+pub struct NStepExpectedSARSA<E: EpisodicEnvironment, ST: Storage = Dense> {
+    stopping_criterion: StoppingCriterion,
+    steps: usize,
+    step_size: StepSize,
+    discount_factor: f64,
+    phantom_env: PhantomData<(E, ST)>,
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: DiscreteAgent<AC, S> + Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > NStepExpectedSARSA<E, ST>
+{
+    /// Creates an estimator that bootstraps from the value of the step `steps` steps ahead.
+    ///
+    /// # Panics
+    /// Panics if `steps` is `0`.
+    pub fn new(episode_limit: usize, steps: usize, alpha: f64, discount_factor: f64) -> Self {
+        assert!(steps > 0, "The number of steps must be at least 1.");
+        Self {
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episode_limit),
+            steps,
+            step_size: StepSize::Constant(alpha),
+            discount_factor,
+            phantom_env: PhantomData,
+        }
+    }
+
+    /// Replaces the [`StepSize`] of the updates, which is constant by default.
+    #[must_use]
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = step_size;
+        self
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: DiscreteAgent<AC, S> + Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > PolicyEstimator for NStepExpectedSARSA<E, ST>
+{
+    type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn policy_search_with_observer(
+        self,
+        environment: &mut Self::Environment,
+        agent: &mut <Self::Environment as Environment>::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
+        self.temporal_difference_policy_search(
+            environment,
+            agent,
+            observer,
+            &TemporalDifferenceConfiguration {
+                stopping_criterion: self.stopping_criterion.clone(),
+                temporal_difference_step: self.steps,
+                step_size: self.step_size,
+                discount_factor: self.discount_factor,
+            },
+        )
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: DiscreteAgent<AC, S> + Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > TemporalDifference<AC, S, AG, E> for NStepExpectedSARSA<E, ST>
+{
+    fn algorithm_specific_evaluation(
+        &self,
+        agent: &AG,
+        action_value: &QTable<S, AC, ST>,
+        action_mask: &ActionMask<S, AC>,
+        next_step: Option<(&S, &AC)>,
+    ) -> f64 {
+        match next_step {
+            Some((next_state, _next_action)) => {
+                let distribution =
+                    agent.action_distribution(next_state, action_value.action_space());
+                let next_values = action_value.observation_values(next_state);
+                action_mask
+                    .legal_indices(next_state)
+                    .map(|index| distribution[index] * next_values[index])
+                    .sum()
+            }
+            None => 0.,
+        }
+    }
+}
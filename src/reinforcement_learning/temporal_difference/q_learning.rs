@@ -1,89 +1,103 @@
 use std::marker::PhantomData;
 
 use crate::{
-    action::DiscreteAction,
     agent::Agent,
-    environment::{Environment, EpisodicEnvironment},
-    observation::DiscreteObservation,
+    environment::{DiscreteEnvironment, Environment, EpisodicEnvironment},
     reinforcement_learning::{
         temporal_difference::{TemporalDifference, TemporalDifferenceConfiguration},
-        DiscretePolicyEstimator, PolicyEstimator,
+        PolicyEstimator, StoppingCriterion, TrainingObserver, TrainingReport,
     },
+    schedule::StepSize,
+    space::{ActionMask, SpaceElement},
+    value_table::{Dense, QTable, Storage},
 };
 
-pub struct QLearning<E: EpisodicEnvironment> {
-    episode_limit: usize,
-    learning_rate: f64,
+pub struct QLearning<E: EpisodicEnvironment, ST: Storage = Dense> {
+    stopping_criterion: StoppingCriterion,
+    step_size: StepSize,
     discount_factor: f64,
-    phantom_env: PhantomData<E>,
+    phantom_env: PhantomData<(E, ST)>,
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > QLearning<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > QLearning<E, ST>
 {
     pub fn new(episode_limit: usize, learning_rate: f64, discount_factor: f64) -> Self {
         Self {
-            episode_limit,
-            learning_rate,
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episode_limit),
+            step_size: StepSize::Constant(learning_rate),
             discount_factor,
             phantom_env: PhantomData,
         }
     }
+
+    /// Replaces the [`StepSize`] of the updates, which is constant by default.
+    #[must_use]
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = step_size;
+        self
+    }
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > PolicyEstimator for QLearning<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > PolicyEstimator for QLearning<E, ST>
 {
     type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
 
-    fn policy_search(
+    fn policy_search_with_observer(
         self,
         environment: &mut Self::Environment,
         agent: &mut <Self::Environment as Environment>::Agent,
-    ) {
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
         self.temporal_difference_policy_search(
             environment,
             agent,
+            observer,
             &TemporalDifferenceConfiguration {
-                episode_limit: self.episode_limit,
+                stopping_criterion: self.stopping_criterion.clone(),
                 temporal_difference_step: 1,
-                learning_rate: self.learning_rate,
+                step_size: self.step_size,
                 discount_factor: self.discount_factor,
             },
-        );
+        )
     }
 }
 
 impl<
-        AC: DiscreteAction,
-        S: DiscreteObservation,
+        AC: SpaceElement,
+        S: SpaceElement,
         AG: Agent<Action = AC, Observation = S>,
-        E: EpisodicEnvironment<Agent = AG>,
-    > TemporalDifference<AC, S, AG, E> for QLearning<E>
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > TemporalDifference<AC, S, AG, E> for QLearning<E, ST>
 {
     fn algorithm_specific_evaluation(
         &self,
         _agent: &AG,
-        action_value: &mut [f64],
+        action_value: &QTable<S, AC, ST>,
+        action_mask: &ActionMask<S, AC>,
         next_step: Option<(&S, &AC)>,
     ) -> f64 {
         match next_step {
-            Some((next_state, _next_action)) => AC::ACTIONS
-                .iter()
-                .map(|discrete_action| {
-                    let index = Self::tabular_index(discrete_action, next_state);
-                    action_value[index]
-                })
-                .max_by(|lhs, rhs| lhs.total_cmp(rhs))
-                .expect("There must be a action with maximum value."),
+            Some((next_state, _next_action)) => {
+                action_value.legal_max_value(next_state, action_mask)
+            }
             None => 0.,
         }
     }
@@ -0,0 +1,149 @@
+use std::marker::PhantomData;
+
+use crate::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, Environment, EpisodicEnvironment},
+    reinforcement_learning::{
+        temporal_difference::eligibility_traces::{
+            EligibilityTraceConfiguration, EligibilityTraceState, EligibilityTraces, TraceType,
+        },
+        PolicyEstimator, StoppingCriterion, TrainingObserver, TrainingReport,
+    },
+    schedule::StepSize,
+    space::SpaceElement,
+    value_table::{Dense, Storage},
+};
+
+/// Watkins's Q(λ), Q-Learning with eligibility traces.
+///
+/// Traces are cut after every exploratory action, since the following rewards no longer
+/// follow the greedy policy being learned.
+pub struct WatkinsQLambda<E: EpisodicEnvironment, ST: Storage = Dense> {
+    stopping_criterion: StoppingCriterion,
+    step_size: StepSize,
+    discount_factor: f64,
+    lambda: f64,
+    trace_type: TraceType,
+    phantom_env: PhantomData<(E, ST)>,
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > WatkinsQLambda<E, ST>
+{
+    /// # Panics
+    /// Panics if `lambda` is not in `[0, 1]`.
+    pub fn new(
+        episode_limit: usize,
+        lambda: f64,
+        alpha: f64,
+        discount_factor: f64,
+        trace_type: TraceType,
+    ) -> Self {
+        assert!((0. ..=1.).contains(&lambda), "Lambda must be in [0, 1].");
+        Self {
+            stopping_criterion: StoppingCriterion::episode_limit_or_convergence(episode_limit),
+            step_size: StepSize::Constant(alpha),
+            discount_factor,
+            lambda,
+            trace_type,
+            phantom_env: PhantomData,
+        }
+    }
+
+    /// Replaces the [`StepSize`] of the updates, which is constant by default. The step
+    /// size of the visited pair is used for all the pairs updated on that step.
+    #[must_use]
+    pub fn with_step_size(mut self, step_size: StepSize) -> Self {
+        self.step_size = step_size;
+        self
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > PolicyEstimator for WatkinsQLambda<E, ST>
+{
+    type Environment = E;
+    type Storage = ST;
+
+    fn stopping_criterion_mut(&mut self) -> &mut StoppingCriterion {
+        &mut self.stopping_criterion
+    }
+
+    fn policy_search_with_observer(
+        self,
+        environment: &mut Self::Environment,
+        agent: &mut <Self::Environment as Environment>::Agent,
+        observer: &mut dyn TrainingObserver<S, AC>,
+    ) -> TrainingReport<S, AC, ST> {
+        self.eligibility_trace_policy_search(
+            environment,
+            agent,
+            observer,
+            &EligibilityTraceConfiguration {
+                stopping_criterion: self.stopping_criterion.clone(),
+                step_size: self.step_size,
+                discount_factor: self.discount_factor,
+                lambda: self.lambda,
+                trace_type: self.trace_type,
+            },
+        )
+    }
+}
+
+impl<
+        AC: SpaceElement,
+        S: SpaceElement,
+        AG: Agent<Action = AC, Observation = S>,
+        E: EpisodicEnvironment<Agent = AG> + DiscreteEnvironment,
+        ST: Storage,
+    > EligibilityTraces<AC, S, AG, E> for WatkinsQLambda<E, ST>
+{
+    fn eligibility_trace_update(
+        &self,
+        agent: &mut AG,
+        observer: &mut dyn TrainingObserver<S, AC>,
+        eligibility_trace_state: &mut EligibilityTraceState<S, AC, ST>,
+        (s, a, r, next_step): (&S, &AC, f64, Option<(&S, &AC)>),
+        eligibility_trace_configuration: &EligibilityTraceConfiguration,
+    ) -> f64 {
+        let EligibilityTraceConfiguration {
+            stopping_criterion: _,
+            step_size: _,
+            discount_factor,
+            lambda,
+            trace_type,
+        } = *eligibility_trace_configuration;
+
+        let index = Self::visit(eligibility_trace_state, s, a);
+        let learning_rate = eligibility_trace_state.step_size(index);
+        let (max_next_value, greedy_next_action) =
+            next_step.map_or((0., true), |(next_state, next_action)| {
+                let action_value = eligibility_trace_state.action_value();
+                let max_next_value =
+                    action_value.legal_max_value(next_state, eligibility_trace_state.action_mask());
+                let next_value = action_value[(next_state, next_action)];
+                (max_next_value, next_value == max_next_value)
+            });
+        let td_error = r + discount_factor * max_next_value - eligibility_trace_state.value(index);
+
+        eligibility_trace_state.increase_trace(index, trace_type, learning_rate);
+        let variation = eligibility_trace_state
+            .update_values(agent, observer, |_, trace| learning_rate * td_error * trace);
+        if greedy_next_action {
+            eligibility_trace_state.decay_traces(discount_factor * lambda);
+        } else {
+            eligibility_trace_state.clear_traces();
+        }
+        variation
+    }
+}
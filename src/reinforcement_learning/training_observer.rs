@@ -0,0 +1,37 @@
+// Docs imports
+#[allow(unused_imports)]
+use crate::reinforcement_learning::PolicyEstimator;
+
+/// Callbacks invoked by a [`PolicyEstimator`] while it searches for a policy.
+///
+/// All callbacks do nothing by default, so implementors only need to override
+/// the events that they are interested in.
+/// `()` can be used when no observation of the training is needed.
+#[allow(unused_variables)]
+pub trait TrainingObserver<S, AC> {
+    /// Called before the first step of episode number `episode`, starting from `1`.
+    fn on_episode_start(&mut self, episode: usize) {}
+
+    /// Called after the [`Environment`](crate::environment::Environment) rewards
+    /// the `action` taken on `observation`.
+    fn on_step(&mut self, observation: &S, action: &AC, reward: f64) {}
+
+    /// Called after the value of the pair `(observation, action)` is updated.
+    fn on_value_update(&mut self, observation: &S, action: &AC, old_value: f64, new_value: f64) {}
+
+    /// Called after the last update of an episode.
+    ///
+    /// # Arguments
+    /// `episode_return`: Undiscounted sum of the rewards of the episode</br>
+    /// `episode_length`: Number of steps of the episode</br>
+    /// `episode_variation`: Sum of the squared changes to values during the episode
+    fn on_episode_end(
+        &mut self,
+        episode_return: f64,
+        episode_length: usize,
+        episode_variation: f64,
+    ) {
+    }
+}
+
+impl<S, AC> TrainingObserver<S, AC> for () {}
@@ -0,0 +1,48 @@
+use std::fmt::Display;
+
+use crate::{
+    reinforcement_learning::StoppingCriterion,
+    space::SpaceElement,
+    value_table::{Dense, ObservationActionTable, QTable, Storage},
+};
+
+// Docs imports
+#[allow(unused_imports)]
+use crate::reinforcement_learning::PolicyEstimator;
+
+/// Result of a [`PolicyEstimator::policy_search`], with tables kept in the
+/// [`Storage`] of the estimator.
+pub struct TrainingReport<S, AC, ST: Storage = Dense> {
+    /// Learned action value of each observation-action pair.
+    pub action_values: QTable<S, AC, ST>,
+    /// Number of times that each observation-action pair had its value updated.
+    pub visit_counts: ObservationActionTable<S, AC, usize, ST>,
+    /// Number of episodes run, zero on searches over continuing tasks.
+    pub episodes: usize,
+    /// Number of actions taken by the agent.
+    pub steps: usize,
+    /// Whether the training stopped because the action values converged.
+    pub converged: bool,
+    /// The [`StoppingCriterion`] that ended the training.
+    pub stopped_by: StoppingCriterion,
+    /// Estimate of the average reward per step, for searches on continuing tasks.
+    pub average_reward: Option<f64>,
+}
+
+/// Writes the visit counts, the action values, the number of episodes, or of steps
+/// on continuing tasks, and the average reward, if any.
+impl<S: SpaceElement, AC: SpaceElement, ST: Storage> Display for TrainingReport<S, AC, ST> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Observation Visit Count")?;
+        write!(f, "{}", self.visit_counts)?;
+        writeln!(f, "Action Value Function")?;
+        write!(f, "{}", self.action_values)?;
+        if let Some(average_reward) = self.average_reward {
+            writeln!(f, "Iterated for {} steps.", self.steps)?;
+            writeln!(f, "Average reward of {average_reward}.")?;
+        } else {
+            writeln!(f, "Iterated for {} episodes.", self.episodes)?;
+        }
+        writeln!(f, "Stopped by {:?}.", self.stopped_by)
+    }
+}
@@ -0,0 +1,105 @@
+mod step_size;
+
+// Re-exports
+pub use self::step_size::StepSize;
+
+pub(crate) use self::step_size::StepSizes;
+
+/// A value that changes with the number of times `k` it was advanced, like the
+/// exploration rate of a policy that explores less as the training goes on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    /// Always the same value.
+    Constant(f64),
+    /// Goes linearly from `start` to `end` over `duration` advances, then stays at `end`.
+    Linear {
+        start: f64,
+        end: f64,
+        duration: usize,
+    },
+    /// `max(start * decay^k, min)`.
+    Exponential { start: f64, decay: f64, min: f64 },
+    /// `start / (k + 1)`.
+    InverseTime { start: f64 },
+    /// Linearly interpolates between the `(k, value)` points, which must be sorted by `k`.
+    /// Takes the value of the first point before it, and of the last point after it.
+    Piecewise(Vec<(usize, f64)>),
+}
+
+/// When a [`Schedule`] is advanced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleTiming {
+    /// At the end of every episode.
+    PerEpisode,
+    /// At the end of every step.
+    PerStep,
+}
+
+impl Schedule {
+    /// Value of the [`Schedule`] after being advanced `k` times.
+    ///
+    /// # Panics
+    /// Panics if a [`Schedule::Piecewise`] has no points.
+    #[must_use]
+    pub fn value(&self, k: usize) -> f64 {
+        match self {
+            Self::Constant(value) => *value,
+            Self::Linear {
+                start,
+                end,
+                duration,
+            } => {
+                if k >= *duration {
+                    *end
+                } else {
+                    start + (end - start) * k as f64 / *duration as f64
+                }
+            }
+            Self::Exponential { start, decay, min } => {
+                (start * decay.powi(i32::try_from(k).unwrap_or(i32::MAX))).max(*min)
+            }
+            Self::InverseTime { start } => start / (k + 1) as f64,
+            Self::Piecewise(points) => {
+                let after = points.partition_point(|(point, _)| *point <= k);
+                match (points.get(after.wrapping_sub(1)), points.get(after)) {
+                    (Some((start_k, start)), Some((end_k, end))) => {
+                        start + (end - start) * (k - start_k) as f64 / (end_k - start_k) as f64
+                    }
+                    (Some((_, value)), None) | (None, Some((_, value))) => *value,
+                    (None, None) => panic!("A piecewise schedule must have at least one point."),
+                }
+            }
+        }
+    }
+
+    /// Smallest and largest values that the [`Schedule`] can take, or `None` if it
+    /// is malformed or unbounded.
+    #[must_use]
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        let bounds = match self {
+            Self::Constant(value) => (*value, *value),
+            Self::Linear {
+                start,
+                end,
+                duration: _,
+            } => (start.min(*end), start.max(*end)),
+            Self::Exponential { start, decay, min } => {
+                if !(0.0f64..=1.).contains(decay) {
+                    return None;
+                }
+                (min.max(start.min(0.)), start.max(0.).max(*min))
+            }
+            Self::InverseTime { start } => (start.min(0.), start.max(0.)),
+            Self::Piecewise(points) => {
+                if points.is_empty() || !points.windows(2).all(|pair| pair[0].0 < pair[1].0) {
+                    return None;
+                }
+                points.iter().fold(
+                    (f64::INFINITY, f64::NEG_INFINITY),
+                    |(min, max), (_, value)| (min.min(*value), max.max(*value)),
+                )
+            }
+        };
+        (bounds.0.is_finite() && bounds.1.is_finite()).then_some(bounds)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::value_table::{Dense, Storage, StorageTable, ValueStorage};
+
+/// Step size `α` of the updates of a value towards its targets, `V = V + α (G - V)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepSize {
+    /// Always `α`, which weights recent targets more and tracks nonstationary problems.
+    Constant(f64),
+    /// `1 / n`, where `n` is the number of updates of the value, which averages all
+    /// the targets.
+    SampleAverage,
+    /// `1 / n^ω`, converges for `ω` in `(0.5, 1]`.
+    Polynomial(f64),
+    /// `α / ō{n}` where `ō{n} = ō{n-1} + α (1 - ō{n-1})` and `ō{0} = 0`. Weights recent
+    /// targets like [`StepSize::Constant`], without the bias towards the initial value.
+    UnbiasedConstant(f64),
+}
+
+/// Step sizes of each value of a table with `rows` rows of `row_len` values.
+pub(crate) struct StepSizes<ST: Storage = Dense> {
+    step_size: StepSize,
+    /// `ō{n}` of each value, only used by [`StepSize::UnbiasedConstant`]
+    traces: StorageTable<ST, f64>,
+}
+
+impl<ST: Storage> StepSizes<ST> {
+    pub fn new(step_size: StepSize, rows: usize, row_len: usize) -> Self {
+        let rows = match step_size {
+            StepSize::UnbiasedConstant(_) => rows,
+            _ => 0,
+        };
+        Self {
+            step_size,
+            traces: StorageTable::<ST, f64>::with_default(rows, row_len, 0.),
+        }
+    }
+
+    /// Step size of an update of the value at `index`, which must be called once per update.
+    ///
+    /// # Arguments
+    /// `visits`: Number of updates of the value, including this one
+    pub fn next(&mut self, index: usize, visits: usize) -> f64 {
+        match self.step_size {
+            StepSize::Constant(alpha) => alpha,
+            StepSize::SampleAverage => 1. / visits as f64,
+            StepSize::Polynomial(omega) => (visits as f64).powf(-omega),
+            StepSize::UnbiasedConstant(alpha) => {
+                self.traces[index] += alpha * (1. - self.traces[index]);
+                alpha / self.traces[index]
+            }
+        }
+    }
+}
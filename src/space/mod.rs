@@ -0,0 +1,238 @@
+use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc};
+
+use crate::{action::DiscreteAction, observation::DiscreteObservation};
+
+// Docs imports
+#[allow(unused_imports)]
+use crate::environment::DiscreteEnvironment;
+
+/// Values that can be members of a [`DiscreteSpace`].
+///
+/// Implemented for every type with the required bounds, so runtime spaces only
+/// need their [`Observation`](crate::observation::Observation) or
+/// [`Action`](crate::action::Action) to derive them.
+pub trait SpaceElement: 'static + Debug + Copy + Eq + Hash {}
+
+impl<T: 'static + Debug + Copy + Eq + Hash> SpaceElement for T {}
+
+/// A finite set of observations or actions, where each member has a position
+/// between `0` and [`DiscreteSpace::len`].
+///
+/// Tabular estimators and policies size and index their tables with the spaces
+/// of a [`DiscreteEnvironment`]. Spaces of [`DiscreteObservation`]s and
+/// [`DiscreteAction`]s are known at compile time, while spaces built with
+/// [`DiscreteSpace::from_elements`] can be loaded at runtime, e.g. from a map or
+/// a configuration file.
+///
+/// Clones share the members of the space.
+#[derive(Clone)]
+pub struct DiscreteSpace<T> {
+    elements: Elements<T>,
+}
+
+#[derive(Clone)]
+enum Elements<T> {
+    /// Members of a [`DiscreteObservation`] or [`DiscreteAction`], indexed by the type
+    Static {
+        len: usize,
+        get: fn(usize) -> T,
+        index_of: fn(&T) -> usize,
+    },
+    /// Members given at runtime, indexed by a [`HashMap`]
+    Listed(Arc<ListedElements<T>>),
+}
+
+struct ListedElements<T> {
+    elements: Vec<T>,
+    indices: HashMap<T, usize>,
+}
+
+impl<S: DiscreteObservation> DiscreteSpace<S> {
+    /// Space of all the [`DiscreteObservation::OBSERVATIONS`].
+    #[must_use]
+    pub fn observations() -> Self {
+        Self {
+            elements: Elements::Static {
+                len: S::COUNT,
+                get: S::from_index,
+                index_of: S::checked_index,
+            },
+        }
+    }
+}
+
+impl<A: DiscreteAction> DiscreteSpace<A> {
+    /// Space of all the [`DiscreteAction::ACTIONS`].
+    #[must_use]
+    pub fn actions() -> Self {
+        Self {
+            elements: Elements::Static {
+                len: A::COUNT,
+                get: A::from_index,
+                index_of: A::checked_index,
+            },
+        }
+    }
+}
+
+impl<T: SpaceElement> DiscreteSpace<T> {
+    /// Space of `elements`, positioned in the order that they are given.
+    ///
+    /// # Panics
+    /// Panics if an element is given more than once.
+    pub fn from_elements(elements: impl IntoIterator<Item = T>) -> Self {
+        let elements: Vec<T> = elements.into_iter().collect();
+        let indices: HashMap<T, usize> = elements
+            .iter()
+            .enumerate()
+            .map(|(index, element)| (*element, index))
+            .collect();
+        assert_eq!(
+            indices.len(),
+            elements.len(),
+            "Elements of a discrete space must be unique."
+        );
+        Self {
+            elements: Elements::Listed(Arc::new(ListedElements { elements, indices })),
+        }
+    }
+
+    /// Number of members of the space.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match &self.elements {
+            Elements::Static { len, .. } => *len,
+            Elements::Listed(listed) => listed.elements.len(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Position of `element` on the space.
+    ///
+    /// # Panics
+    /// Panics if `element` is not a member of the space.
+    #[must_use]
+    pub fn index_of(&self, element: &T) -> usize {
+        match &self.elements {
+            Elements::Static { index_of, .. } => index_of(element),
+            Elements::Listed(listed) => *listed
+                .indices
+                .get(element)
+                .unwrap_or_else(|| panic!("{element:?} must be a member of the discrete space.")),
+        }
+    }
+
+    /// Member at the position `index`, inverse of [`DiscreteSpace::index_of`].
+    ///
+    /// # Panics
+    /// Panics if `index` is not lower than [`DiscreteSpace::len`].
+    #[must_use]
+    pub fn get(&self, index: usize) -> T {
+        match &self.elements {
+            Elements::Static { get, .. } => get(index),
+            Elements::Listed(listed) => listed.elements[index],
+        }
+    }
+
+    /// Iterates over the members of the space, in the order of their positions.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.len()).map(|index| self.get(index))
+    }
+}
+
+impl<T: SpaceElement> Debug for DiscreteSpace<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// The actions of a [`DiscreteSpace`] that are legal on each observation, e.g. the
+/// free squares of a board game or the commands a controller accepts on a state.
+///
+/// Policies only sample and maximize over the legal actions, and estimators only
+/// bootstrap from them. Usually given by [`DiscreteEnvironment::action_mask`].
+///
+/// Clones share the predicate of the mask.
+#[derive(Clone)]
+pub struct ActionMask<S, A> {
+    action_space: DiscreteSpace<A>,
+    /// `None` if every action is always legal
+    is_legal: Option<IsLegal<S, A>>,
+}
+
+/// Whether an action is legal on an observation.
+type IsLegal<S, A> = Arc<dyn Fn(&S, &A) -> bool>;
+
+impl<S, A: SpaceElement> ActionMask<S, A> {
+    /// Every action of `action_space` is legal on every observation.
+    #[must_use]
+    pub fn all(action_space: DiscreteSpace<A>) -> Self {
+        Self {
+            action_space,
+            is_legal: None,
+        }
+    }
+
+    /// The actions of `action_space` for which `is_legal` holds on the observation.
+    pub fn new(
+        action_space: DiscreteSpace<A>,
+        is_legal: impl Fn(&S, &A) -> bool + 'static,
+    ) -> Self {
+        Self {
+            action_space,
+            is_legal: Some(Arc::new(is_legal)),
+        }
+    }
+
+    /// The space of all the actions, legal or not.
+    #[must_use]
+    pub fn action_space(&self) -> &DiscreteSpace<A> {
+        &self.action_space
+    }
+
+    #[must_use]
+    pub fn is_legal(&self, observation: &S, action: &A) -> bool {
+        self.is_legal
+            .as_ref()
+            .is_none_or(|is_legal| is_legal(observation, action))
+    }
+
+    /// Positions on the action space of the legal actions of `observation`.
+    pub fn legal_indices<'a>(&'a self, observation: &'a S) -> impl Iterator<Item = usize> + 'a {
+        (0..self.action_space.len())
+            .filter(move |index| self.is_legal(observation, &self.action_space.get(*index)))
+    }
+
+    /// Replaces the mask of a policy with `action_mask`.
+    ///
+    /// # Panics
+    /// Panics if the action space of `action_mask` isn't the size of the current one.
+    pub(crate) fn replace(&mut self, action_mask: Self) {
+        assert_eq!(
+            action_mask.action_space.len(),
+            self.action_space.len(),
+            "The action mask must be over the action space of the policy."
+        );
+        *self = action_mask;
+    }
+
+    /// Legal actions of `observation`, in the order of the action space.
+    pub fn legal_actions<'a>(&'a self, observation: &'a S) -> impl Iterator<Item = A> + 'a {
+        self.action_space
+            .iter()
+            .filter(move |action| self.is_legal(observation, action))
+    }
+}
+
+impl<S, A: SpaceElement> Debug for ActionMask<S, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ActionMask")
+            .field("action_space", &self.action_space)
+            .field("masked", &self.is_legal.is_some())
+            .finish()
+    }
+}
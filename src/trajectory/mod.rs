@@ -1,6 +1,6 @@
-use crate::{action::Action, observation::Observation};
+use crate::environment::EpisodeEnd;
 
-pub enum Trajectory<S: Observation, A: Action> {
+pub enum Trajectory<S, A> {
     Step {
         observation: S,
         action: A,
@@ -8,5 +8,6 @@ pub enum Trajectory<S: Observation, A: Action> {
     },
     Final {
         observation: S,
+        episode_end: EpisodeEnd,
     },
 }
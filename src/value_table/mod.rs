@@ -0,0 +1,311 @@
+use std::{
+    fmt::{Debug, Display},
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    action::DiscreteAction,
+    observation::DiscreteObservation,
+    space::{ActionMask, DiscreteSpace, SpaceElement},
+};
+
+mod observation_table;
+mod storage;
+
+pub use self::{
+    observation_table::{ObservationTable, VTable},
+    storage::{Dense, DenseValues, Sparse, SparseValues, Storage, ValueStorage},
+};
+
+pub(crate) use self::storage::StorageTable;
+
+// Docs imports
+#[allow(unused_imports)]
+use crate::reinforcement_learning::PolicyEstimator;
+
+/// A table that holds one value for each pair of observation and action of its
+/// [`DiscreteSpace`]s, kept in the [`Storage`] `ST`.
+///
+/// Values are laid out observation major, that is, all the actions of the first
+/// observation, followed by all the actions of the second observation, and so on.
+pub struct ObservationActionTable<S, AC, T: Clone, ST: Storage = Dense> {
+    values: ST::Table<T>,
+    observation_space: DiscreteSpace<S>,
+    action_space: DiscreteSpace<AC>,
+}
+
+/// Table of action values `Q(s, a)` learned by a [`PolicyEstimator`].
+pub type QTable<S, AC, ST = Dense> = ObservationActionTable<S, AC, f64, ST>;
+
+impl<S: DiscreteObservation, AC: DiscreteAction, T: Clone> ObservationActionTable<S, AC, T> {
+    /// Builds a table of all the [`DiscreteObservation`]s and [`DiscreteAction`]s
+    /// from a list of values laid out observation major.
+    ///
+    /// # Panics
+    /// Panics if `values` does not have exactly one value for each observation-action pair.
+    #[must_use]
+    pub fn from_values(values: Vec<T>) -> Self {
+        assert_eq!(
+            values.len(),
+            S::COUNT * AC::COUNT,
+            "There must be one value for each observation-action pair."
+        );
+        Self::from_storage(
+            DiscreteSpace::observations(),
+            DiscreteSpace::actions(),
+            DenseValues::from_values(values, AC::COUNT),
+        )
+    }
+}
+
+impl<S: SpaceElement, AC: SpaceElement, T: Clone> ObservationActionTable<S, AC, T> {
+    /// Flattened values of the table.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        self.values.as_slice()
+    }
+}
+
+impl<S: DiscreteObservation, AC: DiscreteAction, T: Clone, ST: Storage>
+    ObservationActionTable<S, AC, T, ST>
+{
+    /// Builds a table where all pairs of [`DiscreteObservation`] and [`DiscreteAction`]
+    /// have `value`.
+    #[must_use]
+    pub fn filled(value: T) -> Self {
+        Self::with_spaces(
+            DiscreteSpace::observations(),
+            DiscreteSpace::actions(),
+            value,
+        )
+    }
+}
+
+impl<S: SpaceElement, AC: SpaceElement, T: Clone, ST: Storage>
+    ObservationActionTable<S, AC, T, ST>
+{
+    /// Builds a table where all the pairs of `observation_space` and `action_space`
+    /// have `value`.
+    #[must_use]
+    pub fn with_spaces(
+        observation_space: DiscreteSpace<S>,
+        action_space: DiscreteSpace<AC>,
+        value: T,
+    ) -> Self {
+        let values = ST::Table::with_default(observation_space.len(), action_space.len(), value);
+        Self::from_storage(observation_space, action_space, values)
+    }
+
+    /// Builds a table from the values of a [`ValueStorage`] with one row per
+    /// observation of `observation_space`.
+    ///
+    /// # Panics
+    /// Panics if the rows of `values` don't have one value for each action of `action_space`.
+    #[must_use]
+    pub fn from_storage(
+        observation_space: DiscreteSpace<S>,
+        action_space: DiscreteSpace<AC>,
+        values: ST::Table<T>,
+    ) -> Self {
+        assert_eq!(
+            values.row_len(),
+            action_space.len(),
+            "There must be one value for each action on the rows."
+        );
+        Self {
+            values,
+            observation_space,
+            action_space,
+        }
+    }
+
+    /// Index of the pair `(observation, action)` on the flattened table.
+    #[must_use]
+    pub fn tabular_index(&self, observation: &S, action: &AC) -> usize {
+        self.observation_space.index_of(observation) * self.action_space.len()
+            + self.action_space.index_of(action)
+    }
+
+    #[must_use]
+    pub fn get(&self, observation: &S, action: &AC) -> &T {
+        &self.values[self.tabular_index(observation, action)]
+    }
+
+    pub fn get_mut(&mut self, observation: &S, action: &AC) -> &mut T {
+        let index = self.tabular_index(observation, action);
+        &mut self.values[index]
+    }
+
+    /// Values of all the actions of `observation`, in the order of the action space.
+    #[must_use]
+    pub fn observation_values(&self, observation: &S) -> &[T] {
+        self.values
+            .row(self.observation_space.index_of(observation))
+    }
+
+    /// Mutable values of all the actions of `observation`, in the order of the action space.
+    pub fn observation_values_mut(&mut self, observation: &S) -> &mut [T] {
+        self.values
+            .row_mut(self.observation_space.index_of(observation))
+    }
+
+    /// The [`DiscreteSpace`] of the observations of the table.
+    #[must_use]
+    pub fn observation_space(&self) -> &DiscreteSpace<S> {
+        &self.observation_space
+    }
+
+    /// The [`DiscreteSpace`] of the actions of the table.
+    #[must_use]
+    pub fn action_space(&self) -> &DiscreteSpace<AC> {
+        &self.action_space
+    }
+
+    /// Storage of the values of the table.
+    #[must_use]
+    pub fn storage(&self) -> &ST::Table<T> {
+        &self.values
+    }
+
+    /// Iterates over all observation-action pairs and their values.
+    pub fn iter(&self) -> impl Iterator<Item = (S, AC, &T)> {
+        self.observation_space.iter().flat_map(move |observation| {
+            self.action_space
+                .iter()
+                .zip(self.observation_values(&observation))
+                .map(move |(action, value)| (observation, action, value))
+        })
+    }
+}
+
+impl<S: SpaceElement, AC: SpaceElement, ST: Storage> QTable<S, AC, ST> {
+    /// The action with the highest value for `observation`.
+    #[must_use]
+    pub fn greedy_action(&self, observation: &S) -> AC {
+        self.action_space
+            .iter()
+            .zip(self.observation_values(observation))
+            .max_by(|(_, lhs), (_, rhs)| lhs.total_cmp(rhs))
+            .map(|(action, _)| action)
+            .expect("There must be a action with maximum value.")
+    }
+
+    /// The highest value among the actions of `observation`.
+    #[must_use]
+    pub fn max_value(&self, observation: &S) -> f64 {
+        self.observation_values(observation)
+            .iter()
+            .copied()
+            .max_by(f64::total_cmp)
+            .expect("There must be a action with maximum value.")
+    }
+
+    /// Same as [`QTable::greedy_action`], but only among the legal actions of
+    /// `observation` on `action_mask`.
+    ///
+    /// # Panics
+    /// Panics if `observation` has no legal actions.
+    #[must_use]
+    pub fn legal_greedy_action(&self, observation: &S, action_mask: &ActionMask<S, AC>) -> AC {
+        let observation_values = self.observation_values(observation);
+        action_mask
+            .legal_indices(observation)
+            .max_by(|lhs, rhs| observation_values[*lhs].total_cmp(&observation_values[*rhs]))
+            .map(|index| self.action_space.get(index))
+            .expect("The observation must have a legal action.")
+    }
+
+    /// Same as [`QTable::max_value`], but only among the legal actions of
+    /// `observation` on `action_mask`.
+    ///
+    /// # Panics
+    /// Panics if `observation` has no legal actions.
+    #[must_use]
+    pub fn legal_max_value(&self, observation: &S, action_mask: &ActionMask<S, AC>) -> f64 {
+        let observation_values = self.observation_values(observation);
+        action_mask
+            .legal_indices(observation)
+            .map(|index| observation_values[index])
+            .max_by(f64::total_cmp)
+            .expect("The observation must have a legal action.")
+    }
+}
+
+impl<S: SpaceElement, AC: SpaceElement, T: Clone, ST: Storage> Index<(&S, &AC)>
+    for ObservationActionTable<S, AC, T, ST>
+{
+    type Output = T;
+
+    fn index(&self, (observation, action): (&S, &AC)) -> &Self::Output {
+        self.get(observation, action)
+    }
+}
+
+impl<S: SpaceElement, AC: SpaceElement, T: Clone, ST: Storage> IndexMut<(&S, &AC)>
+    for ObservationActionTable<S, AC, T, ST>
+{
+    fn index_mut(&mut self, (observation, action): (&S, &AC)) -> &mut Self::Output {
+        self.get_mut(observation, action)
+    }
+}
+
+/// Indexes the flattened table, see [`ObservationActionTable::tabular_index`].
+impl<S: SpaceElement, AC: SpaceElement, T: Clone, ST: Storage> Index<usize>
+    for ObservationActionTable<S, AC, T, ST>
+{
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.values[index]
+    }
+}
+
+impl<S: SpaceElement, AC: SpaceElement, T: Clone, ST: Storage> IndexMut<usize>
+    for ObservationActionTable<S, AC, T, ST>
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.values[index]
+    }
+}
+
+impl<S: SpaceElement, AC: SpaceElement, T: Clone, ST: Storage> Clone
+    for ObservationActionTable<S, AC, T, ST>
+{
+    fn clone(&self) -> Self {
+        Self::from_storage(
+            self.observation_space.clone(),
+            self.action_space.clone(),
+            self.values.clone(),
+        )
+    }
+}
+
+impl<S: SpaceElement, AC: SpaceElement, T: Clone + Debug, ST: Storage> Debug
+    for ObservationActionTable<S, AC, T, ST>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.iter()
+                    .map(|(observation, action, value)| ((observation, action), value)),
+            )
+            .finish()
+    }
+}
+
+/// Writes one line per observation held by the [`Storage`] with the values of all
+/// of its actions.
+impl<S: SpaceElement, AC: SpaceElement, T: Clone + Display, ST: Storage> Display
+    for ObservationActionTable<S, AC, T, ST>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (observation_index, acts) in self.values.stored_rows() {
+            write!(f, "{:?} ", self.observation_space.get(observation_index))?;
+            for (action, value) in self.action_space.iter().zip(acts) {
+                write!(f, "[{action:?}; {value}] ")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
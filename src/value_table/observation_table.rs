@@ -0,0 +1,159 @@
+use std::{
+    fmt::{Debug, Display},
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    observation::DiscreteObservation,
+    space::{DiscreteSpace, SpaceElement},
+    value_table::{Dense, DenseValues, Storage, ValueStorage},
+};
+
+/// A table that holds one value for each observation of its [`DiscreteSpace`], in
+/// the order of the space, kept in the [`Storage`] `ST`.
+pub struct ObservationTable<S, T: Clone, ST: Storage = Dense> {
+    values: ST::Table<T>,
+    observation_space: DiscreteSpace<S>,
+}
+
+/// Table of state values `V(s)`.
+pub type VTable<S, ST = Dense> = ObservationTable<S, f64, ST>;
+
+impl<S: DiscreteObservation, T: Clone> ObservationTable<S, T> {
+    /// Builds a table of all the [`DiscreteObservation`]s from a list of values in the
+    /// order of [`DiscreteObservation::OBSERVATIONS`].
+    ///
+    /// # Panics
+    /// Panics if `values` does not have exactly one value for each observation.
+    #[must_use]
+    pub fn from_values(values: Vec<T>) -> Self {
+        Self::with_values(DiscreteSpace::observations(), values)
+    }
+}
+
+impl<S: SpaceElement, T: Clone> ObservationTable<S, T> {
+    /// Builds a table from a list of values in the order of `observation_space`.
+    ///
+    /// # Panics
+    /// Panics if `values` does not have exactly one value for each observation.
+    #[must_use]
+    pub fn with_values(observation_space: DiscreteSpace<S>, values: Vec<T>) -> Self {
+        assert_eq!(
+            values.len(),
+            observation_space.len(),
+            "There must be one value for each observation."
+        );
+        Self::from_storage(observation_space, DenseValues::from_values(values, 1))
+    }
+
+    /// Values of the table in the order of the observation space.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        self.values.as_slice()
+    }
+}
+
+impl<S: DiscreteObservation, T: Clone, ST: Storage> ObservationTable<S, T, ST> {
+    /// Builds a table where all [`DiscreteObservation`]s have `value`.
+    #[must_use]
+    pub fn filled(value: T) -> Self {
+        Self::with_space(DiscreteSpace::observations(), value)
+    }
+}
+
+impl<S: SpaceElement, T: Clone, ST: Storage> ObservationTable<S, T, ST> {
+    /// Builds a table where all the observations of `observation_space` have `value`.
+    #[must_use]
+    pub fn with_space(observation_space: DiscreteSpace<S>, value: T) -> Self {
+        let values = ST::Table::with_default(observation_space.len(), 1, value);
+        Self::from_storage(observation_space, values)
+    }
+
+    /// Builds a table from the values of a [`ValueStorage`] with one row per
+    /// observation of `observation_space`.
+    ///
+    /// # Panics
+    /// Panics if the rows of `values` don't have a single value.
+    #[must_use]
+    pub fn from_storage(observation_space: DiscreteSpace<S>, values: ST::Table<T>) -> Self {
+        assert_eq!(
+            values.row_len(),
+            1,
+            "There must be a single value on the rows."
+        );
+        Self {
+            values,
+            observation_space,
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, observation: &S) -> &T {
+        &self.values[self.observation_space.index_of(observation)]
+    }
+
+    pub fn get_mut(&mut self, observation: &S) -> &mut T {
+        &mut self.values[self.observation_space.index_of(observation)]
+    }
+
+    /// The [`DiscreteSpace`] of the observations of the table.
+    #[must_use]
+    pub fn observation_space(&self) -> &DiscreteSpace<S> {
+        &self.observation_space
+    }
+
+    /// Storage of the values of the table.
+    #[must_use]
+    pub fn storage(&self) -> &ST::Table<T> {
+        &self.values
+    }
+
+    /// Iterates over all observations and their values.
+    pub fn iter(&self) -> impl Iterator<Item = (S, &T)> {
+        self.observation_space
+            .iter()
+            .enumerate()
+            .map(|(index, observation)| (observation, &self.values[index]))
+    }
+}
+
+impl<S: SpaceElement, T: Clone, ST: Storage> Index<&S> for ObservationTable<S, T, ST> {
+    type Output = T;
+
+    fn index(&self, observation: &S) -> &Self::Output {
+        self.get(observation)
+    }
+}
+
+impl<S: SpaceElement, T: Clone, ST: Storage> IndexMut<&S> for ObservationTable<S, T, ST> {
+    fn index_mut(&mut self, observation: &S) -> &mut Self::Output {
+        self.get_mut(observation)
+    }
+}
+
+impl<S: SpaceElement, T: Clone, ST: Storage> Clone for ObservationTable<S, T, ST> {
+    fn clone(&self) -> Self {
+        Self::from_storage(self.observation_space.clone(), self.values.clone())
+    }
+}
+
+impl<S: SpaceElement, T: Clone + Debug, ST: Storage> Debug for ObservationTable<S, T, ST> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Writes one line per observation held by the [`Storage`] with its value.
+impl<S: SpaceElement, T: Clone + Display, ST: Storage> Display for ObservationTable<S, T, ST> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (observation_index, values) in self.values.stored_rows() {
+            writeln!(
+                f,
+                "{:?} {}",
+                self.observation_space.get(observation_index),
+                values[0]
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    collections::HashMap,
+    ops::{Index, IndexMut},
+};
+
+/// Storage of the values of a table, organized in rows of `row_len` values.
+///
+/// Tables of observation-action pairs have one row per observation with the values
+/// of all of its actions, so the values of an observation are always contiguous.
+pub trait ValueStorage<T>: Index<usize, Output = T> + IndexMut<usize> + Clone {
+    /// Table of `rows` rows where every value starts as `default`.
+    fn with_default(rows: usize, row_len: usize, default: T) -> Self;
+
+    /// Number of values of a row.
+    fn row_len(&self) -> usize;
+
+    /// Values of the row `row`.
+    fn row(&self, row: usize) -> &[T];
+
+    /// Mutable values of the row `row`.
+    fn row_mut(&mut self, row: usize) -> &mut [T];
+
+    /// Iterates over the rows held in memory and their indices, in increasing order.
+    fn stored_rows<'a>(&'a self) -> impl Iterator<Item = (usize, &'a [T])>
+    where
+        T: 'a;
+}
+
+/// Chooses the [`ValueStorage`] of the tables of estimators and policies.
+pub trait Storage {
+    type Table<T: Clone>: ValueStorage<T>;
+}
+
+/// Table of values of type `T` kept in the [`Storage`] `ST`.
+pub(crate) type StorageTable<ST, T> = <ST as Storage>::Table<T>;
+
+/// Allocates every value up front, the fastest [`Storage`] when most of the
+/// observations are visited.
+pub struct Dense;
+
+impl Storage for Dense {
+    type Table<T: Clone> = DenseValues<T>;
+}
+
+/// Only allocates the rows that are written, for observation spaces too large to
+/// allocate where a small fraction of the observations is ever visited.
+pub struct Sparse;
+
+impl Storage for Sparse {
+    type Table<T: Clone> = SparseValues<T>;
+}
+
+/// [`ValueStorage`] of [`Dense`], a single flattened [`Vec`].
+#[derive(Debug, Clone)]
+pub struct DenseValues<T> {
+    values: Vec<T>,
+    row_len: usize,
+}
+
+impl<T> DenseValues<T> {
+    /// Flattened values of the table.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Builds a table from a list of values laid out row major.
+    ///
+    /// # Panics
+    /// Panics if the length of `values` is not a multiple of `row_len`.
+    #[must_use]
+    pub fn from_values(values: Vec<T>, row_len: usize) -> Self {
+        assert!(
+            row_len > 0 && values.len().is_multiple_of(row_len),
+            "Values must fill a whole number of rows."
+        );
+        Self { values, row_len }
+    }
+}
+
+impl<T: Clone> ValueStorage<T> for DenseValues<T> {
+    fn with_default(rows: usize, row_len: usize, default: T) -> Self {
+        Self::from_values(vec![default; rows * row_len], row_len)
+    }
+
+    fn row_len(&self) -> usize {
+        self.row_len
+    }
+
+    fn row(&self, row: usize) -> &[T] {
+        &self.values[row * self.row_len..(row + 1) * self.row_len]
+    }
+
+    fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.values[row * self.row_len..(row + 1) * self.row_len]
+    }
+
+    fn stored_rows<'a>(&'a self) -> impl Iterator<Item = (usize, &'a [T])>
+    where
+        T: 'a,
+    {
+        self.values.chunks(self.row_len).enumerate()
+    }
+}
+
+impl<T> Index<usize> for DenseValues<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.values[index]
+    }
+}
+
+impl<T> IndexMut<usize> for DenseValues<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.values[index]
+    }
+}
+
+/// [`ValueStorage`] of [`Sparse`], a [`HashMap`] of rows.
+///
+/// Rows that were never written read as the default value. Writing to any value
+/// of a row, even through [`IndexMut`], allocates the whole row.
+#[derive(Debug, Clone)]
+pub struct SparseValues<T> {
+    rows: HashMap<usize, Box<[T]>>,
+    default_row: Box<[T]>,
+    row_count: usize,
+}
+
+impl<T: Clone> SparseValues<T> {
+    fn checked_row(&self, row: usize) -> usize {
+        assert!(
+            row < self.row_count,
+            "Row {row} is out of a table of {} rows.",
+            self.row_count
+        );
+        row
+    }
+}
+
+impl<T: Clone> ValueStorage<T> for SparseValues<T> {
+    fn with_default(rows: usize, row_len: usize, default: T) -> Self {
+        assert!(row_len > 0, "Rows must have at least one value.");
+        Self {
+            rows: HashMap::new(),
+            default_row: vec![default; row_len].into_boxed_slice(),
+            row_count: rows,
+        }
+    }
+
+    fn row_len(&self) -> usize {
+        self.default_row.len()
+    }
+
+    fn row(&self, row: usize) -> &[T] {
+        self.rows
+            .get(&self.checked_row(row))
+            .unwrap_or(&self.default_row)
+    }
+
+    fn row_mut(&mut self, row: usize) -> &mut [T] {
+        let row = self.checked_row(row);
+        self.rows
+            .entry(row)
+            .or_insert_with(|| self.default_row.clone())
+    }
+
+    fn stored_rows<'a>(&'a self) -> impl Iterator<Item = (usize, &'a [T])>
+    where
+        T: 'a,
+    {
+        let mut rows: Vec<_> = self
+            .rows
+            .iter()
+            .map(|(row, values)| (*row, &**values))
+            .collect();
+        rows.sort_unstable_by_key(|(row, _)| *row);
+        rows.into_iter()
+    }
+}
+
+impl<T: Clone> Index<usize> for SparseValues<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        let row_len = self.row_len();
+        &self.row(index / row_len)[index % row_len]
+    }
+}
+
+impl<T: Clone> IndexMut<usize> for SparseValues<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let row_len = self.row_len();
+        &mut self.row_mut(index / row_len)[index % row_len]
+    }
+}
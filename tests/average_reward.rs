@@ -0,0 +1,100 @@
+mod common;
+
+use amnesia::{
+    agent::Agent,
+    environment::{DiscreteEnvironment, Environment},
+    observation::DiscreteObservation,
+    reinforcement_learning::{
+        average_reward::DifferentialSARSA, PolicyEstimator, StoppingCriterion,
+    },
+    space::DiscreteSpace,
+};
+use common::{epsilon_greedy_rover, Chain, EpsilonGreedyRover, Move, INNER};
+
+/// Continuing version of the [`Chain`], where reaching an end gives its reward and
+/// moves the rover back to the middle.
+struct LoopingChain(Chain);
+
+impl Environment for LoopingChain {
+    type Agent = EpsilonGreedyRover;
+
+    fn get_observation(&mut self, _agent: &Self::Agent) -> Option<Chain> {
+        Some(self.0)
+    }
+
+    fn receive_action(&mut self, _agent: &Self::Agent, action: &Move) -> f64 {
+        let index = self.0.index();
+        let next = match action {
+            Move::Left => index - 1,
+            Move::Right => index + 1,
+        };
+        let (next, reward) = match Chain::from_index(next) {
+            Chain::S1 => (Chain::S4, 1.),
+            Chain::S7 => (Chain::S4, 10.),
+            next => (next, 0.),
+        };
+        self.0 = next;
+        reward
+    }
+}
+
+impl DiscreteEnvironment for LoopingChain {
+    fn observation_space(&self) -> DiscreteSpace<Chain> {
+        DiscreteSpace::from_elements(INNER)
+    }
+
+    fn action_space(&self) -> DiscreteSpace<<Self::Agent as Agent>::Action> {
+        DiscreteSpace::actions()
+    }
+}
+
+#[test]
+fn differential_sarsa_converges_on_a_continuing_chain() {
+    let mut environment = LoopingChain(Chain::S4);
+    let mut agent = epsilon_greedy_rover(0.1, 4);
+    let report =
+        DifferentialSARSA::<_>::new(20_000, 0.1, 0.01).policy_search(&mut environment, &mut agent);
+
+    assert_eq!(report.steps, 20_000);
+    assert_eq!(report.episodes, 0);
+    for observation in [Chain::S4, Chain::S5, Chain::S6] {
+        assert_eq!(
+            report.action_values.greedy_action(&observation),
+            Move::Right,
+            "{observation:?}"
+        );
+    }
+}
+
+#[test]
+fn target_average_return_averages_the_rewards_of_the_last_steps() {
+    let target = StoppingCriterion::TargetAverageReturn {
+        target: 2.5,
+        window: 300,
+    };
+    let mut environment = LoopingChain(Chain::S4);
+    let mut agent = epsilon_greedy_rover(0.1, 5);
+    let report = DifferentialSARSA::<_>::new(0, 0.1, 0.01)
+        .with_stopping_criterion(StoppingCriterion::Any(vec![
+            target.clone(),
+            StoppingCriterion::MaxSteps(50_000),
+        ]))
+        .policy_search(&mut environment, &mut agent);
+
+    assert_eq!(report.stopped_by, target);
+    assert!(report.steps >= 300 && report.steps < 50_000);
+    assert_eq!(report.episodes, 0);
+}
+
+#[test]
+#[should_panic(expected = "Continuing tasks have no episodes")]
+fn continuing_tasks_reject_episode_limits() {
+    let mut environment = LoopingChain(Chain::S4);
+    let mut agent = epsilon_greedy_rover(0.1, 6);
+    let _ = DifferentialSARSA::<_>::new(0, 0.1, 0.01)
+        .with_stopping_criterion(StoppingCriterion::Any(vec![
+            StoppingCriterion::MaxEpisodes(10),
+            StoppingCriterion::MaxSteps(100),
+        ]))
+        .policy_search(&mut environment, &mut agent);
+}
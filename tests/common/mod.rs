@@ -0,0 +1,255 @@
+//! Small known models shared by the integration tests.
+#![allow(dead_code)]
+
+use std::{cell::Cell, marker::PhantomData};
+
+use amnesia::{
+    action::DiscreteAction,
+    agent::{Agent, DiscreteAgent, PolicyAgent},
+    environment::{DiscreteEnvironment, Environment, EpisodeEnd, EpisodicEnvironment},
+    model::{TabularMdpEnvironment, TabularModel},
+    observation::DiscreteObservation,
+    policy::{epsilon_greedy::EpsilonGreedyPolicy, Greedy, Policy},
+    random_number_generator::Xoshiro256PlusPlus,
+    space::DiscreteSpace,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteAction)]
+pub enum Move {
+    Left,
+    Right,
+}
+
+/// A chain of 7 cells whose ends are terminal. Reaching the left end rewards 1 and
+/// the right end rewards 10, so the optimal policy always moves right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteObservation)]
+pub enum Chain {
+    S1,
+    S2,
+    S3,
+    S4,
+    S5,
+    S6,
+    S7,
+}
+
+/// Non terminal cells of the [`Chain`].
+pub const INNER: [Chain; 5] = [Chain::S2, Chain::S3, Chain::S4, Chain::S5, Chain::S6];
+
+pub struct MarsChain;
+
+impl MarsChain {
+    pub fn is_terminal(observation: Chain) -> bool {
+        matches!(observation, Chain::S1 | Chain::S7)
+    }
+
+    fn next(observation: Chain, action: Move) -> Chain {
+        let index = observation.index();
+        match action {
+            Move::Left => Chain::from_index(index.saturating_sub(1)),
+            Move::Right => Chain::from_index((index + 1).min(Chain::COUNT - 1)),
+        }
+    }
+}
+
+impl TabularModel<Chain, Move> for MarsChain {
+    fn transitions(
+        &self,
+        observation: &Chain,
+        action: &Move,
+    ) -> impl Iterator<Item = (f64, Chain, f64, bool)> {
+        let next = MarsChain::next(*observation, *action);
+        let reward = match next {
+            Chain::S1 => 1.,
+            Chain::S7 => 10.,
+            _ => 0.,
+        };
+        (!MarsChain::is_terminal(*observation))
+            .then_some((1., next, reward, MarsChain::is_terminal(next)))
+            .into_iter()
+    }
+}
+
+/// Agent that follows a [`Policy`] over the [`Chain`].
+pub struct Rover<P>(pub P);
+
+impl<P: Policy<Action = Move, Observation = Chain>> Agent for Rover<P> {
+    type Action = Move;
+    type Observation = Chain;
+
+    fn act(&self, observation: &Self::Observation) -> Self::Action {
+        self.0.act(observation)
+    }
+
+    fn policy_improvemnt(&mut self, observation: &Self::Observation, action_values: &[f64]) {
+        self.0.policy_improvemnt(observation, action_values);
+    }
+
+    fn policy_improvement_with_visits(
+        &mut self,
+        observation: &Self::Observation,
+        action_values: &[f64],
+        visits: &[usize],
+    ) {
+        self.0
+            .policy_improvement_with_visits(observation, action_values, visits);
+    }
+
+    fn on_episode_end(&mut self) {
+        self.0.on_episode_end();
+    }
+
+    fn on_step_end(&mut self) {
+        self.0.on_step_end();
+    }
+}
+
+impl<P: Policy<Action = Move, Observation = Chain>> PolicyAgent for Rover<P> {
+    type Policy = P;
+
+    fn policy(&self) -> &Self::Policy {
+        &self.0
+    }
+}
+
+pub type EpsilonGreedyRover = Rover<EpsilonGreedyPolicy<Move, Chain, Xoshiro256PlusPlus>>;
+pub type GreedyRover = Rover<Greedy<Move, Chain, Xoshiro256PlusPlus>>;
+
+pub type ChainEnvironment<AG> = TabularMdpEnvironment<MarsChain, AG, Xoshiro256PlusPlus>;
+
+/// Environment whose episodes start on the middle of the chain.
+pub fn chain_environment<AG: Agent<Action = Move, Observation = Chain>>(
+    seed: u64,
+) -> ChainEnvironment<AG> {
+    TabularMdpEnvironment::new(
+        MarsChain,
+        &[(1., Chain::S4)],
+        Xoshiro256PlusPlus::from_seed(seed),
+    )
+    .expect("The chain is a valid model.")
+}
+
+pub fn epsilon_greedy_rover(epsilon: f64, seed: u64) -> EpsilonGreedyRover {
+    Rover(EpsilonGreedyPolicy::new(epsilon, Xoshiro256PlusPlus::from_seed(seed)).unwrap())
+}
+
+/// Rover that always moves right, the optimal policy of the [`Chain`].
+pub fn right_rover(seed: u64) -> GreedyRover {
+    let mut rover = Rover(Greedy::new(Xoshiro256PlusPlus::from_seed(seed)));
+    for observation in INNER {
+        rover.0.policy_improvemnt(&observation, &[0., 1.]);
+    }
+    rover
+}
+
+/// Agent that takes a fixed sequence of moves across its episodes while reporting the
+/// probabilities of the uniformly random policy, and never improves it. Gives
+/// trajectories whose updates can be computed by hand.
+pub struct Scripted {
+    moves: Vec<Move>,
+    next: Cell<usize>,
+}
+
+impl Scripted {
+    pub fn new(moves: &[Move]) -> Self {
+        Self {
+            moves: moves.to_vec(),
+            next: Cell::new(0),
+        }
+    }
+}
+
+impl Agent for Scripted {
+    type Action = Move;
+    type Observation = Chain;
+
+    fn act(&self, _observation: &Self::Observation) -> Self::Action {
+        let next = self.next.get();
+        self.next.set(next + 1);
+        *self
+            .moves
+            .get(next)
+            .expect("The script should have enough moves.")
+    }
+
+    fn policy_improvemnt(&mut self, _observation: &Self::Observation, _action_values: &[f64]) {}
+}
+
+impl DiscreteAgent<Move, Chain> for Scripted {
+    fn action_probability(&self, _action: &Move, _observation: &Chain) -> f64 {
+        0.5
+    }
+}
+
+/// The [`Chain`] truncated after a single step, whose episodes alternate between
+/// starting on S6 and on S5. As every episode from S5 is truncated on S6 or S4, the
+/// values of S5 only come from bootstrapping on the final observation.
+pub struct TruncatedChain<AG> {
+    observation: Chain,
+    steps: usize,
+    episodes: usize,
+    phantom_agent: PhantomData<AG>,
+}
+
+impl<AG> TruncatedChain<AG> {
+    pub fn new() -> Self {
+        Self {
+            observation: Chain::S6,
+            steps: 0,
+            episodes: 0,
+            phantom_agent: PhantomData,
+        }
+    }
+}
+
+impl<AG: Agent<Action = Move, Observation = Chain>> Environment for TruncatedChain<AG> {
+    type Agent = AG;
+
+    fn get_observation(&mut self, _agent: &Self::Agent) -> Option<Chain> {
+        (self.steps == 0 && !MarsChain::is_terminal(self.observation)).then_some(self.observation)
+    }
+
+    fn receive_action(&mut self, _agent: &Self::Agent, action: &Move) -> f64 {
+        let (_, next, reward, _) = MarsChain
+            .transitions(&self.observation, action)
+            .next()
+            .expect("Inner cells have transitions.");
+        self.observation = next;
+        self.steps += 1;
+        reward
+    }
+}
+
+impl<AG: Agent<Action = Move, Observation = Chain>> EpisodicEnvironment for TruncatedChain<AG> {
+    fn reset_environment(&mut self) {
+        self.observation = if self.episodes.is_multiple_of(2) {
+            Chain::S6
+        } else {
+            Chain::S5
+        };
+        self.steps = 0;
+        self.episodes += 1;
+    }
+
+    fn final_observation(&self, _agent: &Self::Agent) -> Chain {
+        self.observation
+    }
+
+    fn episode_end(&self) -> EpisodeEnd {
+        if MarsChain::is_terminal(self.observation) {
+            EpisodeEnd::Terminated
+        } else {
+            EpisodeEnd::Truncated
+        }
+    }
+}
+
+impl<AG: Agent<Action = Move, Observation = Chain>> DiscreteEnvironment for TruncatedChain<AG> {
+    fn observation_space(&self) -> DiscreteSpace<Chain> {
+        DiscreteSpace::observations()
+    }
+
+    fn action_space(&self) -> DiscreteSpace<Move> {
+        DiscreteSpace::actions()
+    }
+}
@@ -0,0 +1,86 @@
+//! Checks the expansion of the derives of the discrete spaces.
+#![cfg(feature = "derive")]
+
+use amnesia::{action::DiscreteAction, observation::DiscreteObservation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteAction)]
+enum Direction {
+    North,
+    South,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteAction)]
+enum Command {
+    Wait,
+    Move(Direction, bool),
+    Turn { clockwise: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DiscreteObservation)]
+struct Position {
+    #[amnesia(range = -2..3)]
+    x: i32,
+    #[amnesia(range = 1..=3)]
+    y: u8,
+    carrying: bool,
+}
+
+#[test]
+fn enums_with_tuple_and_struct_variants() {
+    assert_eq!(
+        Command::ACTIONS,
+        &[
+            Command::Wait,
+            Command::Move(Direction::North, false),
+            Command::Move(Direction::North, true),
+            Command::Move(Direction::South, false),
+            Command::Move(Direction::South, true),
+            Command::Turn { clockwise: false },
+            Command::Turn { clockwise: true },
+        ]
+    );
+}
+
+#[test]
+fn structs_with_ranges() {
+    assert_eq!(Position::OBSERVATIONS.len(), 5 * 3 * 2);
+    assert_eq!(
+        Position::OBSERVATIONS[0],
+        Position {
+            x: -2,
+            y: 1,
+            carrying: false
+        }
+    );
+    assert_eq!(
+        Position::OBSERVATIONS[29],
+        Position {
+            x: 2,
+            y: 3,
+            carrying: true
+        }
+    );
+}
+
+#[test]
+fn indices_round_trip() {
+    for (index, command) in Command::ACTIONS.iter().enumerate() {
+        assert_eq!(command.index(), index);
+        assert_eq!(Command::from_index(index), *command);
+    }
+    for (index, position) in Position::OBSERVATIONS.iter().enumerate() {
+        assert_eq!(position.index(), index);
+        assert_eq!(Position::from_index(index), *position);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Field `x` must be in the range `-2..3`, but is 3.")]
+fn index_panics_on_out_of_range_fields() {
+    let _ = Position {
+        x: 3,
+        y: 1,
+        carrying: false,
+    }
+    .index();
+}
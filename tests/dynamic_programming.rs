@@ -0,0 +1,84 @@
+mod common;
+
+use amnesia::reinforcement_learning::dynamic_programming::{
+    PolicyEvaluation, PolicyIteration, Sweep, ValueIteration,
+};
+use common::{right_rover, Chain, MarsChain, Move, INNER};
+
+/// Values of always moving right on the [`Chain`] with a discount of 0.9.
+const OPTIMAL_VALUES: [(Chain, f64); 7] = [
+    (Chain::S1, 0.),
+    (Chain::S2, 6.561),
+    (Chain::S3, 7.29),
+    (Chain::S4, 8.1),
+    (Chain::S5, 9.),
+    (Chain::S6, 10.),
+    (Chain::S7, 0.),
+];
+
+#[test]
+fn policy_evaluation_converges_to_the_values_of_the_policy() {
+    for sweep in [Sweep::Synchronous, Sweep::InPlace] {
+        let report = PolicyEvaluation::new(0.9, 1e-9, 1000)
+            .with_sweep(sweep)
+            .evaluate(&MarsChain, &right_rover(0));
+        assert!(report.converged);
+        for (observation, value) in OPTIMAL_VALUES {
+            assert!(
+                (report.state_values[&observation] - value).abs() < 1e-6,
+                "{observation:?} with {sweep:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn value_iteration_converges_to_the_optimal_values() {
+    for sweep in [Sweep::Synchronous, Sweep::InPlace] {
+        let report = ValueIteration::new(0.9, 1e-9, 1000)
+            .with_sweep(sweep)
+            .solve::<Chain, Move>(&MarsChain);
+        assert!(report.converged);
+        for (observation, value) in OPTIMAL_VALUES {
+            assert!(
+                (report.state_values[&observation] - value).abs() < 1e-6,
+                "{observation:?} with {sweep:?}"
+            );
+        }
+        for observation in INNER {
+            assert_eq!(
+                report.action_values.greedy_action(&observation),
+                Move::Right
+            );
+        }
+    }
+}
+
+#[test]
+fn policy_iteration_converges_to_the_optimal_values() {
+    let report = PolicyIteration::new(0.9, 1e-9, 1000).solve::<Chain, Move>(&MarsChain);
+    assert!(report.converged);
+    for (observation, value) in OPTIMAL_VALUES {
+        assert!(
+            (report.state_values[&observation] - value).abs() < 1e-6,
+            "{observation:?}"
+        );
+    }
+}
+
+#[test]
+fn policy_iteration_reports_capped_improvements() {
+    let report = PolicyIteration::new(0.9, 1e-9, 1)
+        .with_max_improvements(1)
+        .solve::<Chain, Move>(&MarsChain);
+    assert!(!report.converged);
+
+    let report = PolicyIteration::new(0.9, 1e-9, 1000).solve::<Chain, Move>(&MarsChain);
+    assert!(report.converged);
+    for observation in INNER {
+        assert_eq!(
+            report.action_values.greedy_action(&observation),
+            Move::Right
+        );
+    }
+}
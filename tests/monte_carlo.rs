@@ -0,0 +1,222 @@
+mod common;
+
+use amnesia::{
+    environment::{
+        DiscreteEnvironment, Environment, EpisodicEnvironment, ExploringStartsEnvironment,
+    },
+    model::{TabularMdpEnvironment, TabularModel},
+    random_number_generator::Xoshiro256PlusPlus,
+    reinforcement_learning::{
+        monte_carlo::{
+            ConstantAlphaMonteCarlo, ExploringStartsMonteCarlo, FirstVisitMonteCarlo,
+            ImportanceSampling, OffPolicyMonteCarlo,
+        },
+        PolicyEstimator, StoppingCriterion, TrainingObserver,
+    },
+    space::DiscreteSpace,
+};
+use common::{
+    chain_environment, epsilon_greedy_rover, right_rover, Chain, EpsilonGreedyRover, MarsChain,
+    Move, Scripted, TruncatedChain, INNER,
+};
+
+/// Exploring starts on terminal observations would give episodes without steps.
+#[test]
+fn exploring_starts_skip_terminal_observations() {
+    for seed in 0..5 {
+        let mut environment = chain_environment::<EpsilonGreedyRover>(seed);
+        let mut agent = epsilon_greedy_rover(0.1, seed);
+        let report = ExploringStartsMonteCarlo::<_, _>::new(
+            1.,
+            2_000,
+            Xoshiro256PlusPlus::from_seed(seed + 100),
+        )
+        .with_stopping_criterion(StoppingCriterion::MaxEpisodes(2_000))
+        .policy_search(&mut environment, &mut agent);
+
+        for observation in [Chain::S1, Chain::S7] {
+            for action in [Move::Left, Move::Right] {
+                assert_eq!(report.visit_counts[(&observation, &action)], 0);
+            }
+        }
+
+        for observation in INNER {
+            for action in [Move::Left, Move::Right] {
+                assert!(
+                    report.visit_counts[(&observation, &action)] > 0,
+                    "({observation:?}, {action:?}) was never started from with seed {seed}."
+                );
+            }
+            assert_eq!(
+                report.action_values.greedy_action(&observation),
+                Move::Right,
+                "{observation:?} with seed {seed}"
+            );
+        }
+        assert_eq!(report.action_values[(&Chain::S6, &Move::Right)], 10.);
+    }
+}
+
+/// Environment whose episodes are over on every observation.
+struct Finished;
+
+impl Environment for Finished {
+    type Agent = EpsilonGreedyRover;
+
+    fn get_observation(&mut self, _agent: &Self::Agent) -> Option<Chain> {
+        None
+    }
+
+    fn receive_action(&mut self, _agent: &Self::Agent, _action: &Move) -> f64 {
+        0.
+    }
+}
+
+impl EpisodicEnvironment for Finished {
+    fn reset_environment(&mut self) {}
+
+    fn final_observation(&self, _agent: &Self::Agent) -> Chain {
+        Chain::S1
+    }
+}
+
+impl ExploringStartsEnvironment for Finished {
+    fn reset_to(&mut self, _observation: &Chain) -> bool {
+        false
+    }
+}
+
+impl DiscreteEnvironment for Finished {
+    fn observation_space(&self) -> DiscreteSpace<Chain> {
+        DiscreteSpace::observations()
+    }
+
+    fn action_space(&self) -> DiscreteSpace<Move> {
+        DiscreteSpace::actions()
+    }
+}
+
+#[test]
+#[should_panic(expected = "Exploring starts need a legal action")]
+fn exploring_starts_need_a_pair_to_start_on() {
+    let mut agent = epsilon_greedy_rover(0.1, 0);
+    ExploringStartsMonteCarlo::<_, _>::new(1., 10, Xoshiro256PlusPlus::from_seed(0))
+        .policy_search(&mut Finished, &mut agent);
+}
+
+#[test]
+fn first_visit_monte_carlo_converges_on_the_chain() {
+    let mut environment = chain_environment::<EpsilonGreedyRover>(3);
+    let mut agent = epsilon_greedy_rover(0.3, 3);
+    let report =
+        FirstVisitMonteCarlo::<_>::new(0.9, 2_000).policy_search(&mut environment, &mut agent);
+
+    for observation in INNER {
+        assert_eq!(
+            report.action_values.greedy_action(&observation),
+            Move::Right,
+            "{observation:?}"
+        );
+    }
+    assert_eq!(report.action_values[(&Chain::S6, &Move::Right)], 10.);
+}
+
+/// Counts the steps of a search.
+struct StepCounter(usize);
+
+impl TrainingObserver<Chain, Move> for StepCounter {
+    fn on_step(&mut self, _observation: &Chain, _action: &Move, _reward: f64) {
+        self.0 += 1;
+    }
+}
+
+#[test]
+fn reports_count_steps_apart_from_episodes() {
+    let mut environment = chain_environment::<EpsilonGreedyRover>(0);
+    let mut agent = epsilon_greedy_rover(0.5, 0);
+    let mut counter = StepCounter(0);
+    let report = FirstVisitMonteCarlo::<_>::new(1., 50).policy_search_with_observer(
+        &mut environment,
+        &mut agent,
+        &mut counter,
+    );
+
+    assert_eq!(report.steps, counter.0);
+    assert!(report.steps >= 3 * report.episodes);
+}
+
+#[test]
+fn truncated_episodes_bootstrap_from_the_final_observation() {
+    let mut environment = TruncatedChain::<EpsilonGreedyRover>::new();
+    let mut agent = epsilon_greedy_rover(0.5, 8);
+    let report = ConstantAlphaMonteCarlo::<_>::new(0.5, 0.9, 1_000)
+        .with_stopping_criterion(StoppingCriterion::MaxEpisodes(1_000))
+        .policy_search(&mut environment, &mut agent);
+
+    assert!((report.action_values[(&Chain::S6, &Move::Right)] - 10.).abs() < 1e-6);
+    assert!((report.action_values[(&Chain::S5, &Move::Right)] - 9.).abs() < 1e-6);
+}
+
+/// The [`MarsChain`] where every move costs 1, so the rewards before the end of the
+/// episode set the per-decision returns apart from the ordinary ones.
+struct TolledChain;
+
+impl TabularModel<Chain, Move> for TolledChain {
+    fn transitions(
+        &self,
+        observation: &Chain,
+        action: &Move,
+    ) -> impl Iterator<Item = (f64, Chain, f64, bool)> {
+        MarsChain
+            .transitions(observation, action)
+            .map(|(probability, next, reward, terminal)| (probability, next, reward - 1., terminal))
+    }
+}
+
+/// Episodes `R R R` and `R L R R R` from S4, taken by a uniformly random behaviour
+/// that reports `b = 0.5`, evaluated for the target that always moves right, so every
+/// ratio is 2 after a right move and 0 after a left one. With `γ = 0.9`, the returns
+/// of `(S5, R)` are `-1 + 0.9 * 9 = 7.1` on both episodes, scaled by the ratio 2 of
+/// `(S6, R)` on ordinary sampling, while per decision `-1 + 0.9 * 2 * 9 = 15.2`.
+#[test]
+fn importance_sampling_corrects_returns_on_a_fixed_trajectory() {
+    use Move::{Left as L, Right as R};
+
+    for (importance_sampling, expected) in [
+        (
+            ImportanceSampling::Ordinary,
+            [9., 14.2, 14.373_333_333_333, 30.808],
+        ),
+        (ImportanceSampling::Weighted, [9., 7.1, 5.39, 3.851]),
+        (ImportanceSampling::PerDecision, [9., 15.2, 17.24, 46.448]),
+    ] {
+        let mut environment = TabularMdpEnvironment::new(
+            TolledChain,
+            &[(1., Chain::S4)],
+            Xoshiro256PlusPlus::from_seed(0),
+        )
+        .unwrap();
+        let mut target = right_rover(0);
+        let report = OffPolicyMonteCarlo::<_, _>::new(&mut target, 0.9, 2, importance_sampling)
+            .with_target_improvement(false)
+            .with_stopping_criterion(StoppingCriterion::MaxEpisodes(2))
+            .policy_search(
+                &mut environment,
+                &mut Scripted::new(&[R, R, R, R, L, R, R, R]),
+            );
+
+        let values = [
+            (Chain::S6, Move::Right),
+            (Chain::S5, Move::Right),
+            (Chain::S4, Move::Right),
+            (Chain::S5, Move::Left),
+        ]
+        .map(|(observation, action)| report.action_values[(&observation, &action)]);
+        for (value, expected) in values.into_iter().zip(expected) {
+            assert!(
+                (value - expected).abs() < 1e-9,
+                "{importance_sampling:?}: {value} != {expected}"
+            );
+        }
+    }
+}
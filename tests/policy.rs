@@ -0,0 +1,169 @@
+mod common;
+
+use amnesia::{
+    agent::{Agent, DiscreteAgent},
+    policy::{
+        epsilon_greedy::EpsilonGreedyPolicy, softmax::SoftmaxPolicy, ucb::UcbPolicy, Greedy,
+        Policy, StochasticPolicy,
+    },
+    random_number_generator::Xoshiro256PlusPlus,
+    reinforcement_learning::{temporal_difference::QLearning, PolicyEstimator, StoppingCriterion},
+    schedule::{Schedule, ScheduleTiming},
+    space::{ActionMask, DiscreteSpace},
+};
+use common::{chain_environment, Chain, EpsilonGreedyRover, Move, Rover};
+
+/// Mask where moving left is never legal.
+fn only_right() -> ActionMask<Chain, Move> {
+    ActionMask::new(DiscreteSpace::actions(), |_: &Chain, action: &Move| {
+        *action == Move::Right
+    })
+}
+
+#[test]
+fn ucb_only_takes_legal_actions() {
+    let mut policy = UcbPolicy::<Move, Chain>::new(1.)
+        .unwrap()
+        .with_action_mask(only_right());
+    // Untried illegal actions must not be taken first
+    assert_eq!(policy.act(&Chain::S4), Move::Right);
+
+    policy.policy_improvement_with_visits(&Chain::S4, &[5., 1.], &[0, 3]);
+    assert_eq!(policy.act(&Chain::S4), Move::Right);
+}
+
+#[test]
+fn softmax_normalizes_over_legal_actions() {
+    let mut policy = SoftmaxPolicy::<Move, Chain, _>::new(1., Xoshiro256PlusPlus::from_seed(0))
+        .unwrap()
+        .with_action_mask(only_right());
+    policy.policy_improvemnt(&Chain::S4, &[10., 0.]);
+
+    assert_eq!(policy.action_probability(&Move::Left, &Chain::S4), 0.);
+    assert!((policy.action_probability(&Move::Right, &Chain::S4) - 1.).abs() < 1e-12);
+    assert_eq!(policy.action_distribution(&Chain::S4), vec![0., 1.]);
+    for _ in 0..100 {
+        assert_eq!(policy.act(&Chain::S4), Move::Right);
+    }
+}
+
+#[test]
+fn policies_with_spaces_only_hold_their_observations() {
+    let observation_space = DiscreteSpace::from_elements(vec![Chain::S2, Chain::S4]);
+
+    let mut softmax = SoftmaxPolicy::<Move, Chain, _>::with_spaces(
+        observation_space.clone(),
+        DiscreteSpace::actions(),
+        Schedule::Constant(0.01),
+        ScheduleTiming::PerEpisode,
+        Xoshiro256PlusPlus::from_seed(0),
+    )
+    .unwrap();
+    softmax.policy_improvemnt(&Chain::S4, &[0., 1.]);
+    assert_eq!(softmax.act(&Chain::S4), Move::Right);
+
+    let mut ucb =
+        UcbPolicy::<Move, Chain>::with_spaces(observation_space, DiscreteSpace::actions(), 0.)
+            .unwrap();
+    ucb.policy_improvement_with_visits(&Chain::S2, &[1., 0.], &[1, 1]);
+    assert_eq!(ucb.act(&Chain::S2), Move::Left);
+}
+
+#[test]
+fn action_distribution_follows_the_action_space_of_the_policy() {
+    let policy = Greedy::<Move, Chain, _>::with_spaces(
+        DiscreteSpace::observations(),
+        DiscreteSpace::from_elements(vec![Move::Right]),
+        Xoshiro256PlusPlus::from_seed(0),
+    );
+    assert_eq!(policy.action_distribution(&Chain::S4), vec![1.]);
+}
+
+#[test]
+#[should_panic(expected = "The action mask must be over the action space of the policy.")]
+fn action_masks_must_cover_the_action_space_of_the_policy() {
+    let _ = Greedy::<Move, Chain, _>::with_spaces(
+        DiscreteSpace::observations(),
+        DiscreteSpace::from_elements(vec![Move::Right]),
+        Xoshiro256PlusPlus::from_seed(0),
+    )
+    .with_action_mask(only_right());
+}
+
+#[test]
+fn agent_distribution_matches_the_action_probabilities() {
+    let mut agent =
+        Rover(SoftmaxPolicy::<Move, Chain, _>::new(0.5, Xoshiro256PlusPlus::from_seed(0)).unwrap());
+    agent.policy_improvemnt(&Chain::S3, &[0.3, 1.]);
+
+    let action_space = DiscreteSpace::actions();
+    let distribution = agent.action_distribution(&Chain::S3, &action_space);
+    for (action, probability) in action_space.iter().zip(distribution) {
+        assert!((agent.action_probability(&action, &Chain::S3) - probability).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn schedules_take_their_values_at_fixed_advances() {
+    let linear = Schedule::Linear {
+        start: 0.9,
+        end: 0.1,
+        duration: 8,
+    };
+    let exponential = Schedule::Exponential {
+        start: 0.5,
+        decay: 0.5,
+        min: 0.1,
+    };
+    let piecewise = Schedule::Piecewise(vec![(2, 0.8), (6, 0.4)]);
+
+    for (schedule, expected) in [
+        (linear, [0.9, 0.8, 0.6, 0.1, 0.1]),
+        (exponential, [0.5, 0.25, 0.1, 0.1, 0.1]),
+        (
+            Schedule::InverseTime { start: 0.6 },
+            [0.6, 0.3, 0.15, 0.6 / 9., 0.6 / 101.],
+        ),
+        (piecewise, [0.8, 0.8, 0.7, 0.4, 0.4]),
+    ] {
+        for (k, expected) in [0, 1, 3, 8, 100].into_iter().zip(expected) {
+            let value = schedule.value(k);
+            assert!(
+                (value - expected).abs() < 1e-12,
+                "{schedule:?} at {k}: {value} != {expected}"
+            );
+        }
+    }
+}
+
+/// Estimators advance the schedule at the end of every episode or step, as given by
+/// its timing, so `ε` ends on the value after as many advances.
+#[test]
+fn epsilon_follows_the_timing_of_its_schedule() {
+    let schedule = Schedule::Linear {
+        start: 0.9,
+        end: 0.,
+        duration: 1_000,
+    };
+    for timing in [ScheduleTiming::PerEpisode, ScheduleTiming::PerStep] {
+        let mut environment = chain_environment::<EpsilonGreedyRover>(0);
+        let mut agent = Rover(
+            EpsilonGreedyPolicy::with_schedule(
+                schedule.clone(),
+                timing,
+                Xoshiro256PlusPlus::from_seed(0),
+            )
+            .unwrap(),
+        );
+        let report = QLearning::<_>::new(20, 0.5, 0.9)
+            .with_stopping_criterion(StoppingCriterion::MaxEpisodes(20))
+            .policy_search(&mut environment, &mut agent);
+
+        let advances = match timing {
+            ScheduleTiming::PerEpisode => report.episodes,
+            ScheduleTiming::PerStep => report.steps,
+        };
+        assert!(report.steps > report.episodes);
+        assert_eq!(agent.0.epsilon(), schedule.value(advances), "{timing:?}");
+    }
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use amnesia::{
+    reinforcement_learning::{
+        prediction::{
+            FirstVisitMonteCarloPrediction, TemporalDifferenceLambdaPrediction,
+            TemporalDifferencePrediction, ValueEstimator,
+        },
+        temporal_difference::TraceType,
+        StoppingCriterion,
+    },
+    schedule::StepSize,
+    value_table::{Sparse, ValueStorage},
+};
+use common::{chain_environment, right_rover, Chain, GreedyRover};
+
+#[test]
+fn sparse_prediction_only_stores_visited_observations() {
+    let mut environment = chain_environment::<GreedyRover>(0);
+    let agent = right_rover(0);
+    let report = FirstVisitMonteCarloPrediction::<_, Sparse>::new(1., 10)
+        .value_prediction(&mut environment, &agent);
+
+    let stored: Vec<_> = report
+        .state_values
+        .storage()
+        .stored_rows()
+        .map(|(index, _)| index)
+        .collect();
+    assert_eq!(stored, vec![3, 4, 5]);
+    for observation in [Chain::S4, Chain::S5, Chain::S6] {
+        assert_eq!(report.state_values[&observation], 10.);
+    }
+    assert_eq!(report.state_values[&Chain::S2], 0.);
+    assert_eq!(report.visit_counts[&Chain::S2], 0);
+}
+
+#[test]
+fn temporal_difference_prediction_converges_to_the_values_of_the_policy() {
+    let mut environment = chain_environment::<GreedyRover>(0);
+    let agent = right_rover(0);
+    let td = TemporalDifferencePrediction::<_>::new(500, 0.5, 0.9)
+        .value_prediction(&mut environment, &agent);
+    let td_lambda =
+        TemporalDifferenceLambdaPrediction::<_>::new(500, 0.5, 0.5, 0.9, TraceType::Accumulating)
+            .value_prediction(&mut environment, &agent);
+
+    for report in [td, td_lambda] {
+        for (observation, value) in [(Chain::S4, 8.1), (Chain::S5, 9.), (Chain::S6, 10.)] {
+            assert!(
+                (report.state_values[&observation] - value).abs() < 1e-6,
+                "{observation:?}"
+            );
+        }
+    }
+}
+
+/// Three episodes `S4 → S5 → S6 → S7` with `γ = 1`. The targets of S6 are always 10,
+/// while S5 bootstraps from the value S6 had before the episode updated it, so from 0
+/// on the first one, and S4 likewise from S5.
+#[test]
+fn step_sizes_weight_the_targets_of_each_observation() {
+    let (root_2, root_3) = (2f64.sqrt(), 3f64.sqrt());
+    let polynomial_s5 = 10. / root_2 + (10. - 10. / root_2) / root_3;
+
+    for (step_size, expected) in [
+        (StepSize::Constant(0.5), [1.25, 5., 8.75]),
+        // The average of the targets, `(0 + 10 + 10) / 3` for S5
+        (StepSize::SampleAverage, [5. / 3., 20. / 3., 10.]),
+        (
+            StepSize::Polynomial(0.5),
+            [10. / root_2 / root_3, polynomial_s5, 10.],
+        ),
+        // Step sizes 1, 1 / (2 - α) and 4 / 7 for α = 0.5
+        (StepSize::UnbiasedConstant(0.5), [80. / 21., 60. / 7., 10.]),
+    ] {
+        let mut environment = chain_environment::<GreedyRover>(0);
+        let report = TemporalDifferencePrediction::<_>::new(3, 0.5, 1.)
+            .with_step_size(step_size)
+            .with_stopping_criterion(StoppingCriterion::MaxEpisodes(3))
+            .value_prediction(&mut environment, &right_rover(0));
+
+        for (observation, expected) in [Chain::S4, Chain::S5, Chain::S6].into_iter().zip(expected) {
+            let value = report.state_values[&observation];
+            assert!(
+                (value - expected).abs() < 1e-12,
+                "{step_size:?} on {observation:?}: {value} != {expected}"
+            );
+        }
+    }
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use amnesia::space::{ActionMask, DiscreteSpace};
+use common::{Chain, Move, INNER};
+
+#[test]
+fn static_spaces_follow_the_declaration_order() {
+    let observations = DiscreteSpace::<Chain>::observations();
+    assert_eq!(observations.len(), 7);
+    assert_eq!(observations.index_of(&Chain::S3), 2);
+    assert_eq!(observations.get(6), Chain::S7);
+
+    let actions = DiscreteSpace::<Move>::actions();
+    assert_eq!(
+        actions.iter().collect::<Vec<_>>(),
+        vec![Move::Left, Move::Right]
+    );
+}
+
+#[test]
+fn listed_spaces_follow_the_given_order() {
+    let space = DiscreteSpace::from_elements([Chain::S6, Chain::S2, Chain::S4]);
+    assert_eq!(space.len(), 3);
+    assert!(!space.is_empty());
+    for (index, element) in space.iter().enumerate() {
+        assert_eq!(space.index_of(&element), index);
+        assert_eq!(space.get(index), element);
+    }
+    assert_eq!(space.index_of(&Chain::S2), 1);
+}
+
+#[test]
+#[should_panic(expected = "must be unique")]
+fn listed_spaces_reject_repeated_elements() {
+    let _ = DiscreteSpace::from_elements([Chain::S2, Chain::S2]);
+}
+
+#[test]
+#[should_panic(expected = "must be a member of the discrete space")]
+fn listed_spaces_reject_foreign_elements() {
+    let space = DiscreteSpace::from_elements(INNER);
+    let _ = space.index_of(&Chain::S1);
+}
+
+#[test]
+fn all_actions_are_legal_without_a_mask() {
+    let mask = ActionMask::<Chain, Move>::all(DiscreteSpace::actions());
+    for observation in INNER {
+        assert_eq!(
+            mask.legal_indices(&observation).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+    }
+}
+
+#[test]
+fn masks_only_keep_the_legal_actions() {
+    // Moving left is illegal next to the left end
+    let mask = ActionMask::new(DiscreteSpace::actions(), |observation: &Chain, action| {
+        !(*observation == Chain::S2 && *action == Move::Left)
+    });
+    assert_eq!(mask.action_space().len(), 2);
+
+    assert!(!mask.is_legal(&Chain::S2, &Move::Left));
+    assert!(mask.is_legal(&Chain::S2, &Move::Right));
+    assert_eq!(mask.legal_indices(&Chain::S2).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(
+        mask.legal_actions(&Chain::S2).collect::<Vec<_>>(),
+        vec![Move::Right]
+    );
+    assert_eq!(
+        mask.legal_actions(&Chain::S3).collect::<Vec<_>>(),
+        vec![Move::Left, Move::Right]
+    );
+}
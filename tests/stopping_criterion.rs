@@ -0,0 +1,137 @@
+mod common;
+
+use std::time::Duration;
+
+use amnesia::{
+    agent::Agent,
+    model::{TabularMdpEnvironment, TabularModel},
+    random_number_generator::Xoshiro256PlusPlus,
+    reinforcement_learning::{
+        monte_carlo::ConstantAlphaMonteCarlo,
+        prediction::{
+            NStepTemporalDifferencePrediction, TemporalDifferenceLambdaPrediction,
+            TemporalDifferencePrediction, ValueEstimator,
+        },
+        temporal_difference::{DoubleQLearning, QLearning, SARSALambda, TraceType},
+        PolicyEstimator, StoppingCriterion,
+    },
+};
+use common::{
+    chain_environment, epsilon_greedy_rover, right_rover, Chain, EpsilonGreedyRover, GreedyRover,
+    Move,
+};
+
+/// Chain whose moves never leave the current cell, so its episodes never end.
+struct Endless;
+
+impl TabularModel<Chain, Move> for Endless {
+    fn transitions(
+        &self,
+        observation: &Chain,
+        _action: &Move,
+    ) -> impl Iterator<Item = (f64, Chain, f64, bool)> {
+        std::iter::once((1., *observation, 0., false))
+    }
+}
+
+fn endless_environment<AG: Agent<Action = Move, Observation = Chain>>(
+) -> TabularMdpEnvironment<Endless, AG, Xoshiro256PlusPlus> {
+    TabularMdpEnvironment::new(
+        Endless,
+        &[(1., Chain::S4)],
+        Xoshiro256PlusPlus::from_seed(0),
+    )
+    .expect("The endless chain is a valid model.")
+}
+
+#[test]
+fn empty_windows_are_never_met() {
+    for criterion in [
+        StoppingCriterion::ValueChangeTolerance {
+            tolerance: f64::INFINITY,
+            window: 0,
+        },
+        StoppingCriterion::TargetAverageReturn {
+            target: f64::NEG_INFINITY,
+            window: 0,
+        },
+    ] {
+        let mut environment = chain_environment::<GreedyRover>(0);
+        let report = TemporalDifferencePrediction::<_>::new(0, 0.1, 1.)
+            .with_stopping_criterion(StoppingCriterion::Any(vec![
+                criterion,
+                StoppingCriterion::MaxEpisodes(20),
+            ]))
+            .value_prediction(&mut environment, &right_rover(0));
+
+        assert_eq!(report.episodes, 20);
+        assert_eq!(report.stopped_by, StoppingCriterion::MaxEpisodes(20));
+    }
+}
+
+#[test]
+fn step_budgets_truncate_endless_episodes() {
+    let budget = StoppingCriterion::MaxSteps(25);
+
+    let reports = [
+        QLearning::<_>::new(10, 0.1, 0.9)
+            .with_stopping_criterion(budget.clone())
+            .policy_search(
+                &mut endless_environment(),
+                &mut epsilon_greedy_rover(0.1, 0),
+            ),
+        SARSALambda::<_>::new(10, 0.5, 0.1, 0.9, TraceType::Replacing)
+            .with_stopping_criterion(budget.clone())
+            .policy_search(
+                &mut endless_environment(),
+                &mut epsilon_greedy_rover(0.1, 0),
+            ),
+        DoubleQLearning::<_, _>::new(10, 0.1, 0.9, Xoshiro256PlusPlus::from_seed(0))
+            .with_stopping_criterion(budget.clone())
+            .policy_search(
+                &mut endless_environment(),
+                &mut epsilon_greedy_rover(0.1, 0),
+            ),
+        ConstantAlphaMonteCarlo::<_>::new(0.1, 0.9, 10)
+            .with_stopping_criterion(budget.clone())
+            .policy_search(
+                &mut endless_environment(),
+                &mut epsilon_greedy_rover(0.1, 0),
+            ),
+    ];
+    for report in reports {
+        assert_eq!(report.episodes, 1);
+        assert_eq!(report.steps, 25);
+        assert_eq!(report.stopped_by, budget);
+    }
+
+    let predictions = [
+        TemporalDifferencePrediction::<_>::new(10, 0.1, 0.9)
+            .with_stopping_criterion(budget.clone())
+            .value_prediction(&mut endless_environment(), &right_rover(0)),
+        NStepTemporalDifferencePrediction::<_>::new(10, 3, 0.1, 0.9)
+            .with_stopping_criterion(budget.clone())
+            .value_prediction(&mut endless_environment(), &right_rover(0)),
+        TemporalDifferenceLambdaPrediction::<_>::new(10, 0.5, 0.1, 0.9, TraceType::Accumulating)
+            .with_stopping_criterion(budget.clone())
+            .value_prediction(&mut endless_environment(), &right_rover(0)),
+    ];
+    for report in predictions {
+        assert_eq!(report.episodes, 1);
+        assert_eq!(report.stopped_by, budget);
+    }
+}
+
+#[test]
+fn time_budgets_truncate_endless_episodes() {
+    let budget = StoppingCriterion::WallClock(Duration::from_millis(20));
+    let report = QLearning::<_>::new(10, 0.1, 0.9)
+        .with_stopping_criterion(budget.clone())
+        .policy_search(
+            &mut endless_environment::<EpsilonGreedyRover>(),
+            &mut epsilon_greedy_rover(0.1, 0),
+        );
+
+    assert_eq!(report.episodes, 1);
+    assert_eq!(report.stopped_by, budget);
+}
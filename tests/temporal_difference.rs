@@ -0,0 +1,181 @@
+mod common;
+
+use amnesia::{
+    random_number_generator::Xoshiro256PlusPlus,
+    reinforcement_learning::{
+        temporal_difference::{
+            DoubleQLearning, NStepExpectedSARSA, NStepQLearning, NStepSARSA, QLearning,
+            SARSALambda, TraceType, TrueOnlineSARSALambda, WatkinsQLambda, SARSA,
+        },
+        PolicyEstimator, StoppingCriterion, TrainingReport,
+    },
+    schedule::StepSize,
+};
+use common::{
+    chain_environment, epsilon_greedy_rover, Chain, EpsilonGreedyRover, Move, Scripted,
+    TruncatedChain, INNER,
+};
+
+/// Checks that the greedy actions of `report` always move right.
+fn assert_moves_right(report: &TrainingReport<Chain, Move>, estimator: &str) {
+    for observation in INNER {
+        assert_eq!(
+            report.action_values.greedy_action(&observation),
+            Move::Right,
+            "{observation:?} with {estimator}"
+        );
+    }
+}
+
+#[test]
+fn q_learning_converges_on_the_chain() {
+    let mut environment = chain_environment::<EpsilonGreedyRover>(0);
+    let mut agent = epsilon_greedy_rover(0.5, 0);
+    let report = QLearning::<_>::new(2_000, 0.2, 0.9).policy_search(&mut environment, &mut agent);
+
+    assert_moves_right(&report, "Q-Learning");
+    assert!((report.action_values[(&Chain::S6, &Move::Right)] - 10.).abs() < 1e-3);
+    assert!((report.action_values[(&Chain::S5, &Move::Right)] - 9.).abs() < 1e-2);
+}
+
+#[test]
+fn watkins_q_lambda_converges_on_the_chain() {
+    let mut environment = chain_environment::<EpsilonGreedyRover>(1);
+    let mut agent = epsilon_greedy_rover(0.5, 1);
+    let report = WatkinsQLambda::<_>::new(2_000, 0.8, 0.2, 0.9, TraceType::Replacing)
+        .policy_search(&mut environment, &mut agent);
+
+    assert_moves_right(&report, "Watkins Q(λ)");
+    assert!((report.action_values[(&Chain::S6, &Move::Right)] - 10.).abs() < 1e-3);
+}
+
+#[test]
+fn double_q_learning_converges_on_the_chain() {
+    let mut environment = chain_environment::<EpsilonGreedyRover>(2);
+    let mut agent = epsilon_greedy_rover(0.5, 2);
+    let report = DoubleQLearning::<_, _>::new(2_000, 0.2, 0.9, Xoshiro256PlusPlus::from_seed(102))
+        .policy_search(&mut environment, &mut agent);
+
+    assert_moves_right(&report, "Double Q-Learning");
+}
+
+/// The value of S5 is only learned from the greedy value of S6 where its episodes are
+/// truncated, `γ max Q(S6, ·) = 0.9 * 10`.
+#[test]
+fn truncated_episodes_bootstrap_from_the_final_observation() {
+    let mut environment = TruncatedChain::<EpsilonGreedyRover>::new();
+    let mut agent = epsilon_greedy_rover(0.5, 7);
+    let report = SARSA::<_>::new(1_000, 0.5, 0.9)
+        .with_stopping_criterion(StoppingCriterion::MaxEpisodes(1_000))
+        .policy_search(&mut environment, &mut agent);
+
+    assert!((report.action_values[(&Chain::S6, &Move::Right)] - 10.).abs() < 1e-6);
+    assert!((report.action_values[(&Chain::S5, &Move::Right)] - 9.).abs() < 1e-6);
+}
+
+/// Sample averages of each table only count the updates of that table, so both of
+/// them average the terminal reward of `(S6, Right)` exactly.
+#[test]
+fn double_q_learning_tables_count_their_own_updates() {
+    let mut environment = TruncatedChain::<EpsilonGreedyRover>::new();
+    let mut agent = epsilon_greedy_rover(0.5, 9);
+    let report = DoubleQLearning::<_, _>::new(200, 0.5, 0.9, Xoshiro256PlusPlus::from_seed(109))
+        .with_step_size(StepSize::SampleAverage)
+        .with_stopping_criterion(StoppingCriterion::MaxEpisodes(200))
+        .policy_search(&mut environment, &mut agent);
+
+    assert!(report.visit_counts[(&Chain::S6, &Move::Right)] > 2);
+    assert_eq!(report.action_values[(&Chain::S6, &Move::Right)], 10.);
+}
+
+/// Two episodes from S4, `R R R` and then `L R R L R R R`, whose moves against the
+/// greedy values make the bootstrapped values of the three estimators differ.
+fn two_episode_script() -> Scripted {
+    use Move::{Left as L, Right as R};
+    Scripted::new(&[R, R, R, L, R, R, L, R, R, R])
+}
+
+/// Two-step returns with `α = 0.5` and `γ = 0.9`. The first episode learns
+/// `Q(S5, R) = 0.5 * (0 + 0.9 * 10) = 4.5` and `Q(S6, R) = 5`. On the second,
+/// `(S3, R)` bootstraps `γ² Q(S5, ·)` on the exploratory `(S5, L)`, `(S5, L)` on
+/// `(S5, R)` and `(S4, R)` on `(S6, R)`.
+#[test]
+fn n_step_estimators_follow_their_returns_on_a_fixed_trajectory() {
+    let values_on = |report: TrainingReport<Chain, Move>| {
+        [
+            (Chain::S3, Move::Right),
+            (Chain::S5, Move::Left),
+            (Chain::S4, Move::Right),
+            (Chain::S5, Move::Right),
+            (Chain::S6, Move::Right),
+        ]
+        .map(|(observation, action)| report.action_values[(&observation, &action)])
+    };
+    let criterion = StoppingCriterion::MaxEpisodes(2);
+
+    let sarsa = NStepSARSA::<_>::new(2, 2, 0.5, 0.9)
+        .with_stopping_criterion(criterion.clone())
+        .policy_search(
+            &mut chain_environment::<Scripted>(0),
+            &mut two_episode_script(),
+        );
+    let q_learning = NStepQLearning::<_>::new(2, 2, 0.5, 0.9)
+        .with_stopping_criterion(criterion.clone())
+        .policy_search(
+            &mut chain_environment::<Scripted>(0),
+            &mut two_episode_script(),
+        );
+    let expected_sarsa = NStepExpectedSARSA::<_>::new(2, 2, 0.5, 0.9)
+        .with_stopping_criterion(criterion)
+        .policy_search(
+            &mut chain_environment::<Scripted>(0),
+            &mut two_episode_script(),
+        );
+
+    // SARSA bootstraps Q(S5, L) = 0, Q-learning max Q(S5, ·) = 4.5 and Expected SARSA
+    // the average of both under the uniform policy
+    for (report, expected) in [
+        (sarsa, [0., 1.8225, 2.025, 6.75, 7.5]),
+        (q_learning, [1.8225, 1.8225, 2.025, 6.75, 7.5]),
+        (expected_sarsa, [0.91125, 0.91125, 1.0125, 6.75, 7.5]),
+    ] {
+        for (value, expected) in values_on(report).into_iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-12, "{value} != {expected}");
+        }
+    }
+}
+
+/// Episodes `R R R` and `R L R R R` from S4 with `λ = 0.8`, `α = 0.5` and `γ = 0.9`.
+/// The first episode gives both estimators `Q(S4, R) = 0.5 * 10 * 0.72²`,
+/// `Q(S5, R) = 0.5 * 10 * 0.72` and `Q(S6, R) = 5`. On the second, `(S4, R)` and
+/// `(S5, R)` are revisited, so the accumulating traces of SARSA(λ) and the dutch traces
+/// of True Online SARSA(λ) diverge.
+#[test]
+fn lambda_estimators_follow_their_traces_on_a_fixed_trajectory() {
+    use Move::{Left as L, Right as R};
+    let script = || Scripted::new(&[R, R, R, R, L, R, R, R]);
+    let criterion = StoppingCriterion::MaxEpisodes(2);
+
+    let sarsa_lambda = SARSALambda::<_>::new(2, 0.8, 0.5, 0.9, TraceType::Accumulating)
+        .with_stopping_criterion(criterion.clone())
+        .policy_search(&mut chain_environment::<Scripted>(0), &mut script());
+    let true_online = TrueOnlineSARSALambda::<_>::new(2, 0.8, 0.5, 0.9)
+        .with_stopping_criterion(criterion)
+        .policy_search(&mut chain_environment::<Scripted>(0), &mut script());
+
+    for (report, expected) in [
+        (sarsa_lambda, [5.332_805_683_2, 2.298_274_56, 5.85, 7.5]),
+        (true_online, [4.769_798_4, 2.449_44, 5.85, 7.5]),
+    ] {
+        let values = [
+            (Chain::S4, Move::Right),
+            (Chain::S5, Move::Left),
+            (Chain::S5, Move::Right),
+            (Chain::S6, Move::Right),
+        ]
+        .map(|(observation, action)| report.action_values[(&observation, &action)]);
+        for (value, expected) in values.into_iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-9, "{value} != {expected}");
+        }
+    }
+}
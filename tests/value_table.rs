@@ -0,0 +1,68 @@
+mod common;
+
+use amnesia::{
+    space::{ActionMask, DiscreteSpace},
+    value_table::{DenseValues, QTable, Sparse, SparseValues, VTable, ValueStorage},
+};
+use common::{Chain, Move, INNER};
+
+#[test]
+fn sparse_values_only_store_written_rows() {
+    let mut values = SparseValues::with_default(4, 2, -1.);
+    assert_eq!(values.row_len(), 2);
+    assert_eq!(values.stored_rows().count(), 0);
+    assert_eq!(values.row(3), &[-1., -1.]);
+
+    values.row_mut(2)[1] = 5.;
+    values[1] = 3.;
+    assert_eq!(values[5], 5.);
+    assert_eq!(values[4], -1.);
+    assert_eq!(values[7], -1.);
+
+    let stored: Vec<_> = values.stored_rows().collect();
+    assert_eq!(stored, vec![(0, &[-1., 3.][..]), (2, &[-1., 5.][..])]);
+}
+
+#[test]
+#[should_panic(expected = "out of a table of 4 rows")]
+fn sparse_values_reject_rows_out_of_the_table() {
+    let values = SparseValues::with_default(4, 2, 0.);
+    let _ = values.row(4);
+}
+
+#[test]
+fn sparse_tables_read_like_dense_ones() {
+    let observation_space = DiscreteSpace::from_elements(INNER);
+    let mut sparse = VTable::<_, Sparse>::with_space(observation_space.clone(), 0.);
+    let mut dense = VTable::<_>::with_space(observation_space, 0.);
+    for (observation, value) in [(Chain::S3, 2.), (Chain::S6, 7.)] {
+        sparse[&observation] = value;
+        dense[&observation] = value;
+    }
+
+    for observation in INNER {
+        assert_eq!(sparse[&observation], dense[&observation]);
+    }
+    assert_eq!(sparse.storage().stored_rows().count(), 2);
+    assert_eq!(dense.storage().stored_rows().count(), INNER.len());
+    assert_eq!(dense.as_slice(), &[0., 2., 0., 0., 7.]);
+    assert_eq!(
+        DenseValues::from_values(vec![0., 2., 0., 0., 7.], 1).as_slice(),
+        dense.as_slice()
+    );
+}
+
+#[test]
+fn greedy_actions_only_consider_legal_actions() {
+    let mut action_values = QTable::<Chain, Move>::filled(0.);
+    action_values[(&Chain::S2, &Move::Left)] = 1.;
+    let mask = ActionMask::new(DiscreteSpace::actions(), |observation: &Chain, action| {
+        !(*observation == Chain::S2 && *action == Move::Left)
+    });
+
+    assert_eq!(action_values.greedy_action(&Chain::S2), Move::Left);
+    assert_eq!(
+        action_values.legal_greedy_action(&Chain::S2, &mask),
+        Move::Right
+    );
+}